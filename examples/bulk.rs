@@ -1,7 +1,7 @@
 use clap::Parser;
 use std::thread;
 use std::time::Duration;
-use ww_bear::{BulkWriteData, Bus, StatusRegister};
+use ww_bear::{BulkWriteData, Bus, OperatingMode, StatusRegister};
 
 #[derive(Parser)]
 struct Args {
@@ -30,8 +30,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // and torque on each motor individually first.
     for &id in &args.ids {
         bus.ping(id)?;
-        bus.write_mode(id, 2)?;
-        bus.write_torque_enable(id, 1)?;
+        bus.write_mode(id, OperatingMode::Position)?;
+        bus.enable_torque(id)?;
     }
     println!("Connected to motors {:?}", args.ids);
 
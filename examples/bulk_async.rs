@@ -1,7 +1,7 @@
 use clap::Parser;
 use tokio::time::{sleep, Duration};
 use ww_bear::asynchronous::Bus;
-use ww_bear::{BulkWriteData, StatusRegister};
+use ww_bear::{BulkWriteData, OperatingMode, StatusRegister};
 
 #[derive(Parser)]
 struct Args {
@@ -31,8 +31,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // and torque on each motor individually first.
     for &id in &args.ids {
         bus.ping(id).await?;
-        bus.write_mode(id, 2).await?;
-        bus.write_torque_enable(id, 1).await?;
+        bus.write_mode(id, OperatingMode::Position).await?;
+        bus.enable_torque(id).await?;
     }
     println!("Connected to motors {:?}", args.ids);
 
@@ -1,7 +1,7 @@
 use clap::Parser;
 use std::thread;
 use std::time::Duration;
-use ww_bear::Bus;
+use ww_bear::{Bus, OperatingMode};
 
 #[derive(Parser)]
 struct Args {
@@ -29,8 +29,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     bus.ping(args.id)?;
     println!("Connected to motor {}", args.id);
 
-    bus.write_mode(args.id, 2)?;
-    bus.write_torque_enable(args.id, 1)?;
+    bus.write_mode(args.id, OperatingMode::Position)?;
+    bus.enable_torque(args.id)?;
     bus.write_goal_pos(args.id, args.position)?;
     println!("Goal position set to {:.4} rad", args.position);
 
@@ -1,5 +1,6 @@
 use clap::Parser;
 use tokio::time::{Duration, sleep};
+use ww_bear::OperatingMode;
 use ww_bear::asynchronous::Bus;
 
 #[derive(Parser)]
@@ -29,8 +30,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     bus.ping(args.id).await?;
     println!("Connected to motor {}", args.id);
 
-    bus.write_mode(args.id, 2).await?;
-    bus.write_torque_enable(args.id, 1).await?;
+    bus.write_mode(args.id, OperatingMode::Position).await?;
+    bus.enable_torque(args.id).await?;
     bus.write_goal_pos(args.id, args.position).await?;
     println!("Goal position set to {:.4} rad", args.position);
 
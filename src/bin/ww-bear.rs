@@ -0,0 +1,283 @@
+//! Command-line tool for working with the motors on a BEAR bus.
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use strum::IntoEnumIterator;
+use ww_bear::monitor::{CsvSink, JsonLinesSink, MonitorConfig};
+use ww_bear::{AnyRegister, Bus, ConfigRegister, MotorConfig, RegisterValue, StatusRegister, ValueType};
+
+#[derive(Parser)]
+#[command(name = "ww-bear", version, about)]
+struct Args {
+    /// Serial port (e.g. /dev/ttyUSB0)
+    #[arg(short, long, env = "WW_BEAR_PORT")]
+    port: String,
+    /// Baud rate
+    #[arg(short, long, default_value_t = 8_000_000)]
+    baud: u32,
+    /// Response timeout padding in milliseconds
+    #[arg(short, long, default_value_t = 10)]
+    response_timeout_padding: u64,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Find the motors on the bus
+    Scan {
+        /// Motor IDs to scan, comma-separated. If omitted, scans all IDs.
+        #[arg(short, long, value_delimiter = ',')]
+        ids: Vec<u8>,
+        /// Scan every supported baud rate instead of only `--baud`
+        #[arg(short, long)]
+        all_bauds: bool,
+    },
+    /// Ping a motor
+    Ping {
+        /// Motor ID
+        id: u8,
+    },
+    /// Read a register, such as `PresentPos` or `p_gain_pos`
+    Read {
+        /// Motor ID
+        id: u8,
+        /// Register name
+        #[arg(value_parser = parse_register)]
+        register: AnyRegister,
+    },
+    /// Write a register, such as `GoalPos` or `limit_i_max`
+    Write {
+        /// Motor ID
+        id: u8,
+        /// Register name
+        #[arg(value_parser = parse_register)]
+        register: AnyRegister,
+        /// The value to write, an integer or a float depending on the register
+        #[arg(allow_negative_numbers = true)]
+        value: String,
+    },
+    /// Print every config register of a motor as TOML
+    DumpConfig {
+        /// Motor ID
+        id: u8,
+        /// Write the config to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Apply a config written by `dump-config` to a motor
+    LoadConfig {
+        /// Motor ID
+        id: u8,
+        /// The TOML config file
+        file: PathBuf,
+        /// Save the config to flash
+        #[arg(short, long)]
+        save: bool,
+        /// Move the motor to the ID in the file, instead of keeping its current ID
+        #[arg(long)]
+        use_file_id: bool,
+    },
+    /// Save the config registers of a motor to flash
+    Save {
+        /// Motor ID
+        id: u8,
+    },
+    /// Set the absolute position of a motor with a backup battery
+    SetAbsPos {
+        /// Motor ID
+        id: u8,
+        /// Target position in radians
+        #[arg(allow_negative_numbers = true)]
+        position: f32,
+        /// Tolerance in radians (0 = adjust homing offset; non-zero = find nearest multi-turn match)
+        #[arg(short, long, default_value_t = 0.0)]
+        tolerance: f32,
+    },
+    /// Enable or disable torque
+    Torque {
+        /// Motor ID
+        id: u8,
+        /// The new torque state
+        state: TorqueArg,
+    },
+    /// Repeatedly read status registers from several motors
+    Monitor {
+        /// Motor IDs, comma-separated
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        ids: Vec<u8>,
+        /// Status registers to read, comma-separated
+        #[arg(short, long, value_delimiter = ',', value_parser = parse_status_register,
+            default_values = ["PresentPos", "PresentVel", "PresentIq"])]
+        registers: Vec<StatusRegister>,
+        /// Reads per second
+        #[arg(long, default_value_t = 10.0, value_parser = parse_rate)]
+        rate: f32,
+        /// Stop after this many reads
+        #[arg(short, long)]
+        count: Option<u64>,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = MonitorFormat::Csv)]
+        format: MonitorFormat,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MonitorFormat {
+    /// Comma-separated values, with a header row
+    Csv,
+    /// One JSON object per line
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TorqueArg {
+    On,
+    Off,
+}
+
+/// Normalise a register name, so `PresentPos`, `presentpos` and `present_pos` all match.
+fn normalise(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn parse_register(name: &str) -> Result<AnyRegister, String> {
+    let name = normalise(name);
+    ConfigRegister::iter()
+        .map(AnyRegister::from)
+        .chain(StatusRegister::iter().map(AnyRegister::from))
+        .find(|register| normalise(register.info().name) == name)
+        .ok_or_else(|| format!("unknown register, expected one of {}", register_names()))
+}
+
+fn parse_status_register(name: &str) -> Result<StatusRegister, String> {
+    match parse_register(name)? {
+        AnyRegister::Status(register) => Ok(register),
+        AnyRegister::Config(_) => Err("only status registers can be monitored".into()),
+    }
+}
+
+fn parse_rate(rate: &str) -> Result<f32, String> {
+    match rate.parse::<f32>() {
+        Ok(rate) if rate > 0.0 && rate.is_finite() => Ok(rate),
+        Ok(_) => Err("the rate must be a positive number".into()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn register_names() -> String {
+    ww_bear::registers::REGISTERS
+        .iter()
+        .map(|info| info.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn parse_value(register: AnyRegister, value: &str) -> Result<RegisterValue, Box<dyn Error>> {
+    Ok(match register.info().value_type {
+        ValueType::U32 => RegisterValue::U32(value.parse()?),
+        ValueType::F32 => RegisterValue::F32(value.parse()?),
+    })
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+    let args = Args::parse();
+    let mut bus = Bus::open(&args.port, args.baud)?;
+    let padding = Duration::from_millis(args.response_timeout_padding);
+    bus.set_response_timeout_padding(padding);
+
+    match args.command {
+        Command::Scan { ids, all_bauds } => {
+            let ids = if ids.is_empty() { (0..=0xFD).collect() } else { ids };
+            let on_found = |found: ww_bear::FoundMotor| {
+                if found.warning.is_empty() {
+                    println!("ID {:>3} at {} baud", found.motor_id, found.baud_rate);
+                } else {
+                    println!(
+                        "ID {:>3} at {} baud [flags: {}]",
+                        found.motor_id, found.baud_rate, found.warning
+                    );
+                }
+            };
+            if all_bauds {
+                bus.scan_baud_rates(ids, padding, on_found)?;
+            } else {
+                bus.scan(ids, padding, on_found)?;
+            }
+        },
+        Command::Ping { id } => {
+            let response = bus.ping(id)?;
+            println!("ID {id}: ok [flags: {}]", response.warning);
+        },
+        Command::Read { id, register } => {
+            let value = bus.read_value(id, register)?.data;
+            match register.info().unit {
+                Some(unit) => println!("{register} = {value} {unit}"),
+                None => println!("{register} = {value}"),
+            }
+        },
+        Command::Write { id, register, value } => {
+            let value = parse_value(register, &value)?;
+            bus.write_value(id, register, value)?;
+        },
+        Command::DumpConfig { id, output } => {
+            let config = toml::to_string(&bus.read_all_config(id)?)?;
+            match output {
+                Some(path) => std::fs::write(path, config)?,
+                None => print!("{config}"),
+            }
+        },
+        Command::LoadConfig {
+            id,
+            file,
+            save,
+            use_file_id,
+        } => {
+            let mut config: MotorConfig = toml::from_str(&std::fs::read_to_string(file)?)?;
+            if !use_file_id {
+                config.id = id.into();
+            }
+            let written = bus.apply_config(id, &config, save)?;
+            println!("{written} registers written");
+        },
+        Command::Save { id } => {
+            bus.save_config(id)?;
+        },
+        Command::SetAbsPos {
+            id,
+            position,
+            tolerance,
+        } => {
+            bus.set_absolute_position(id, position, tolerance)?;
+        },
+        Command::Torque { id, state } => {
+            match state {
+                TorqueArg::On => bus.enable_torque(id)?,
+                TorqueArg::Off => bus.disable_torque(id)?,
+            };
+        },
+        Command::Monitor {
+            ids,
+            registers,
+            rate,
+            count,
+            format,
+        } => {
+            let mut config = MonitorConfig::new(&ids, &registers, rate).ok_or("the rate is too low")?;
+            config.cycles = count;
+            let stdout = std::io::stdout().lock();
+            match format {
+                MonitorFormat::Csv => bus.monitor(&config, &mut CsvSink::new(stdout))?,
+                MonitorFormat::Json => bus.monitor(&config, &mut JsonLinesSink::new(stdout))?,
+            }
+        },
+    }
+    Ok(())
+}
@@ -1,8 +1,8 @@
-use crate::error::{BufferTooSmallError, ReadError, TransferError, WriteError};
+use crate::error::{BufferTooSmallError, InvalidMessage, MotorError, ReadError, TransferError, WriteError};
 use crate::protocol::{PACKET_ERROR, PACKET_ID, PACKET_LEN, Response};
-use crate::{ErrorFlags, checksum};
+use crate::{ErrorFlags, ErrorPolicy, RetryOn, RetryPolicy, checksum};
 use core::time::Duration;
-use log::{debug, trace};
+use log::{debug, trace, warn};
 
 /// Default buffer type.
 ///
@@ -54,6 +54,14 @@ where
     pub(crate) write_buffer: Buffer,
     /// Additional padding added on to message response timeout calculations
     pub(crate) response_timeout_padding: Duration,
+    /// How responses reporting [`crate::ERROR_FLAGS`] are treated.
+    pub(crate) error_policy: ErrorPolicy,
+    /// How failed single motor transfers are retried.
+    pub(crate) retry_policy: RetryPolicy,
+    /// Whether writes, saves and homing wait for the motor to acknowledge them.
+    pub(crate) acknowledge_writes: bool,
+    /// The ID of the [`crate::BulkPlan`] whose packet is held in the write buffer, if any.
+    pub(crate) stamped_plan: Option<u32>,
 }
 
 impl<SerialPort, Buffer> core::fmt::Debug for Bus<SerialPort, Buffer>
@@ -65,6 +73,9 @@ where
         f.debug_struct("Bus")
             .field("serial_port", &self.serial_port)
             .field("baud_rate", &self.baud_rate)
+            .field("error_policy", &self.error_policy)
+            .field("retry_policy", &self.retry_policy)
+            .field("acknowledge_writes", &self.acknowledge_writes)
             .finish_non_exhaustive()
     }
 }
@@ -175,6 +186,10 @@ where
             used_bytes: 0,
             write_buffer,
             response_timeout_padding: Duration::from_millis(3),
+            error_policy: ErrorPolicy::default(),
+            retry_policy: RetryPolicy::default(),
+            acknowledge_writes: true,
+            stamped_plan: None,
         }
     }
 
@@ -200,10 +215,51 @@ where
         self.response_timeout_padding = padding;
     }
 
+    /// Get the policy for responses whose error byte contains any [`crate::ERROR_FLAGS`].
+    pub fn error_policy(&self) -> ErrorPolicy {
+        self.error_policy
+    }
+
+    /// Set the policy for responses whose error byte contains any [`crate::ERROR_FLAGS`].
+    ///
+    /// Defaults to [`ErrorPolicy::Strict`].
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) {
+        self.error_policy = policy;
+    }
+
+    /// Get the policy for retrying failed single motor transfers.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Set the policy for retrying failed single motor transfers.
+    ///
+    /// Defaults to [`RetryPolicy::NEVER`].
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Check if writes, [`Self::save_config`] and [`Self::set_absolute_position`] wait for an acknowledgement.
+    pub fn acknowledge_writes(&self) -> bool {
+        self.acknowledge_writes
+    }
+
+    /// Set whether writes, [`Self::save_config`] and [`Self::set_absolute_position`] wait for an acknowledgement.
+    ///
+    /// When disabled, the instruction is only sent, and the returned [`Response`] echoes the motor ID
+    /// with no warning flags.
+    /// Instructions sent to the broadcast ID are never acknowledged, so they never wait for a reply.
+    ///
+    /// Defaults to `true`.
+    pub fn set_acknowledge_writes(&mut self, acknowledge: bool) {
+        self.acknowledge_writes = acknowledge;
+    }
+
     /// Write a raw instruction to a stream, and read a single raw response.
     ///
-    /// This function also checks that the packet ID of the status response matches the one from the instruction.
-    /// and that the error byte does not contain any [`crate::ERROR_FLAGS`]. [`crate::WARNING_FLAGS`] are allowed.
+    /// This function also checks that the packet ID of the status response matches the one from the instruction,
+    /// and applies the [`ErrorPolicy`] to the error byte (see [`Self::read_response`]).
+    /// Failed attempts are retried according to the [`RetryPolicy`].
     pub(crate) async fn transfer_single<F>(
         &mut self,
         packet_id: u8,
@@ -215,11 +271,84 @@ where
     where
         F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
     {
-        self.write_packet(packet_id, instruction_id, parameter_count, encode_parameters)
-            .await?;
-        let response = self.read_response(expected_response_parameters).await?;
-        crate::error::InvalidPacketId::check(response.motor_id, packet_id)?;
-        Ok(response)
+        self.stamped_plan = None;
+        let packet_len = Self::make_packet(
+            self.write_buffer.as_mut(),
+            packet_id,
+            instruction_id,
+            parameter_count,
+            encode_parameters,
+        )
+        .map_err(WriteError::from)?;
+        let policy = self.retry_policy;
+        let mut attempt = 1;
+        let response_len = loop {
+            let error = match self
+                .transfer_attempt(packet_len, packet_id, expected_response_parameters)
+                .await
+            {
+                Ok(response_len) => break response_len,
+                Err(e) => e,
+            };
+            let retry = match Self::retry_kind(&error) {
+                Some(kind) if attempt < policy.max_attempts && policy.retry_on.contains(kind) => kind,
+                _ => return Err(error),
+            };
+            warn!(
+                "transfer to motor {:#04X} failed on attempt {}/{}: {:?}, retrying",
+                packet_id, attempt, policy.max_attempts, retry
+            );
+            attempt += 1;
+            self.wait(policy.backoff).await?;
+        };
+        Ok(self.packet_response(response_len))
+    }
+
+    /// Send the packet in the write buffer and read the reply, returning the length of the reply.
+    async fn transfer_attempt(
+        &mut self,
+        packet_len: usize,
+        packet_id: u8,
+        expected_response_parameters: u8,
+    ) -> Result<usize, TransferError<SerialPort::Error>> {
+        self.send_packet(packet_len).await?;
+        let timeout =
+            message_transfer_time(expected_response_parameters as u32, self.baud_rate) + self.response_timeout_padding;
+        let response_len = self.read_checked_packet(timeout).await?;
+        crate::error::InvalidPacketId::check(self.read_buffer.as_ref()[PACKET_ID], packet_id)?;
+        Ok(response_len)
+    }
+
+    /// Classify a failed transfer as one of the [`RetryOn`] kinds, if it is one.
+    fn retry_kind(error: &TransferError<SerialPort::Error>) -> Option<RetryOn> {
+        match error {
+            TransferError::ReadError(ReadError::Io(e)) if SerialPort::is_timeout_error(e) => Some(RetryOn::TIMEOUT),
+            TransferError::ReadError(ReadError::InvalidMessage(InvalidMessage::InvalidChecksum(_))) => {
+                Some(RetryOn::INVALID_CHECKSUM)
+            },
+            TransferError::ReadError(ReadError::InvalidMessage(InvalidMessage::InvalidPacketId(_))) => {
+                Some(RetryOn::INVALID_PACKET_ID)
+            },
+            _ => None,
+        }
+    }
+
+    /// Wait for `duration`, discarding anything received in the meantime.
+    pub(crate) async fn wait(&mut self, duration: Duration) -> Result<(), ReadError<SerialPort::Error>> {
+        if duration.is_zero() {
+            return Ok(());
+        }
+        let deadline = self.serial_port.make_deadline(duration);
+        loop {
+            match self.serial_port.read(self.read_buffer.as_mut(), &deadline).await {
+                Ok(_) => continue,
+                Err(e) if SerialPort::is_timeout_error(&e) => break,
+                Err(e) => return Err(ReadError::Io(e)),
+            }
+        }
+        self.read_len = 0;
+        self.used_bytes = 0;
+        Ok(())
     }
     /// Write a packet to the bus.
     pub(crate) fn make_packet<F>(
@@ -261,6 +390,7 @@ where
     where
         F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
     {
+        self.stamped_plan = None;
         let packet_len = Self::make_packet(
             self.write_buffer.as_mut(),
             packet_id,
@@ -268,7 +398,11 @@ where
             parameter_count,
             encode_parameters,
         )?;
-        let packet = &self.write_buffer.as_ref()[..packet_len];
+        self.send_packet(packet_len).await
+    }
+
+    /// Send the first `packet_len` bytes of the write buffer.
+    pub(crate) async fn send_packet(&mut self, packet_len: usize) -> Result<(), WriteError<SerialPort::Error>> {
         // Throw away old data in the read buffer and the kernel read buffer.
         // We don't do this when reading a reply, because we might receive multiple replies for one instruction,
         // and read() can potentially read more than one reply per syscall.
@@ -277,12 +411,28 @@ where
         self.serial_port
             .discard_input_buffer()
             .map_err(WriteError::DiscardBuffer)?;
+        self.queue_packet(packet_len).await
+    }
+
+    /// Send the first `packet_len` bytes of the write buffer, keeping any replies that have not been read yet.
+    ///
+    /// This lets several requests go out before their replies are read, see [`Self::send_packet`].
+    pub(crate) async fn queue_packet(&mut self, packet_len: usize) -> Result<(), WriteError<SerialPort::Error>> {
+        let packet = &self.write_buffer.as_ref()[..packet_len];
         trace!("sending packet: {:02X?}", packet);
         self.serial_port.write_all(packet).await.map_err(WriteError::Write)?;
         Ok(())
-        // self.write_packet_raw(&self.write_buffer.as_ref()[..packet_len])
     }
 
+    /// The largest number of parameters a reply can have to fit the read buffer.
+    pub(crate) fn max_reply_parameters(&self) -> usize {
+        self.read_buffer.as_ref().len().saturating_sub(HEADER_SIZE + 2)
+    }
+
+    /// Read a single response.
+    ///
+    /// If the error byte contains any [`crate::ERROR_FLAGS`], the response is rejected with a [`MotorError`]
+    /// or accepted according to the [`ErrorPolicy`]. [`crate::WARNING_FLAGS`] are always allowed.
     pub(crate) async fn read_response(
         &mut self,
         expected_parameters: u8,
@@ -291,25 +441,60 @@ where
         self.read_response_timeout(timeout).await
     }
 
-    async fn read_response_timeout(
+    /// Read a single response without applying the [`ErrorPolicy`].
+    pub(crate) async fn read_response_unchecked(
         &mut self,
         timeout: Duration,
     ) -> Result<Response<&[u8]>, ReadError<SerialPort::Error>> {
         let deadline = self.serial_port.make_deadline(timeout);
-        let packet = self.read_packet_deadline(deadline).await?;
-        let response = Response {
+        let packet_len = self.read_packet_deadline(deadline).await?;
+        Ok(self.packet_response(packet_len))
+    }
+
+    pub(crate) async fn read_response_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Response<&[u8]>, ReadError<SerialPort::Error>> {
+        let packet_len = self.read_checked_packet(timeout).await?;
+        Ok(self.packet_response(packet_len))
+    }
+
+    /// Read a packet and apply the [`ErrorPolicy`] to its error byte.
+    ///
+    /// Returns the length of the packet at the start of the read buffer, see [`Self::read_packet_deadline`].
+    async fn read_checked_packet(&mut self, timeout: Duration) -> Result<usize, ReadError<SerialPort::Error>> {
+        let deadline = self.serial_port.make_deadline(timeout);
+        let packet_len = self.read_packet_deadline(deadline).await?;
+        let packet = &self.read_buffer.as_ref()[..packet_len];
+        let motor_id = packet[PACKET_ID];
+        let flags = ErrorFlags::from_bits_truncate(packet[PACKET_ERROR]);
+        if let Err(e) = MotorError::check(motor_id, flags) {
+            match self.error_policy {
+                ErrorPolicy::Strict => return Err(e.into()),
+                ErrorPolicy::WarnOnly => warn!("{}", e),
+                ErrorPolicy::Ignore => (),
+            }
+        }
+        Ok(packet_len)
+    }
+
+    /// Parse the packet of the given length at the start of the read buffer.
+    fn packet_response(&self, packet_len: usize) -> Response<&[u8]> {
+        let packet = &self.read_buffer.as_ref()[..packet_len];
+        Response {
             motor_id: packet[PACKET_ID],
             warning: ErrorFlags::from_bits_truncate(packet[PACKET_ERROR]),
             data: &packet[5..],
-        };
-        Ok(response)
+        }
     }
 
-    /// returns a packet including header + parameters
+    /// Read a packet into the start of the read buffer.
+    ///
+    /// Returns the length of the packet, including the header and parameters but not the checksum.
     async fn read_packet_deadline(
         &mut self,
         deadline: SerialPort::Instant,
-    ) -> Result<&[u8], ReadError<SerialPort::Error>> {
+    ) -> Result<usize, ReadError<SerialPort::Error>> {
         // Check that the read buffer is large enough to hold atleast a instruction packet with 0 parameters.
         crate::error::BufferTooSmallError::check(HEADER_SIZE + 2, self.read_buffer.as_mut().len())?; //todo check size is correct
 
@@ -361,8 +546,7 @@ where
 
         // Mark the whole message as "used_bytes", so that the next call to `remove_garbage()` removes it.
         self.used_bytes += message_len;
-        let packet = &self.read_buffer.as_ref()[..parameters_end];
-        Ok(packet)
+        Ok(parameters_end)
     }
     /// Remove leading garbage data from the read buffer.
     fn remove_garbage(&mut self) {
@@ -0,0 +1,278 @@
+//! Several asynchronous buses driven as one, for robots with more than one serial chain.
+//!
+//! A [`BusSet`] owns one [`Bus`] per serial chain and a list of joints, each addressing a motor on one of the
+//! buses. Bulk transfers address every joint at once: they are split per bus, run on all buses concurrently,
+//! and the replies are merged back into one entry per joint. A cycle therefore takes as long as the slowest
+//! chain, rather than the sum of all chains.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::task::Poll;
+
+use super::Bus;
+use super::bus::DefaultBuffer;
+use crate::error::{AddJointError, BusSetError, DeviceCountError, ReadError};
+use crate::protocol::{REGISTER_BYTES, Response};
+use crate::registers::{RegisterSet, WritableRegisterSet};
+use crate::{BulkWriteData, StatusRegister};
+
+/// One owned reply per joint, as returned by [`BusSet::bulk_read`].
+type JointReplies<E> = Vec<Result<Response<Vec<u8>>, ReadError<E>>>;
+
+/// One decoded reply per joint, as returned by [`BusSet::bulk_read_typed`].
+type TypedJointReplies<R, E> = Vec<Result<Response<<R as RegisterSet>::Values>, ReadError<E>>>;
+
+/// A motor of a [`BusSet`], addressed by its name or index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Joint {
+    /// The name of the joint.
+    pub name: String,
+
+    /// The index of the bus the motor is connected to.
+    pub bus: usize,
+
+    /// The ID of the motor on its bus.
+    pub motor_id: u8,
+}
+
+/// Several asynchronous buses driven as one, see [`BusSet::bulk_read_write`].
+///
+/// Joints are numbered in the order they are added, and every bulk transfer takes and returns one entry per joint
+/// in that order.
+pub struct BusSet<SerialPort, Buffer = DefaultBuffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    buses: Vec<Bus<SerialPort, Buffer>>,
+    joints: Vec<Joint>,
+    /// The indices of the joints on each bus, in the order they were added.
+    bus_joints: Vec<Vec<usize>>,
+}
+
+impl<SerialPort, Buffer> BusSet<SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Create a set of buses without any joints, numbered in the order they are given.
+    pub fn new(buses: impl IntoIterator<Item = Bus<SerialPort, Buffer>>) -> Self {
+        let buses: Vec<_> = buses.into_iter().collect();
+        let bus_joints = buses.iter().map(|_| Vec::new()).collect();
+        Self {
+            buses,
+            joints: Vec::new(),
+            bus_joints,
+        }
+    }
+
+    /// Add a joint for the motor `motor_id` on bus `bus`, and return the index of the joint.
+    pub fn add_joint(&mut self, name: impl Into<String>, bus: usize, motor_id: u8) -> Result<usize, AddJointError> {
+        let name = name.into();
+        let Some(bus_joints) = self.bus_joints.get_mut(bus) else {
+            return Err(AddJointError::UnknownBus { bus });
+        };
+        if let Some(joint) = self.joints.iter().position(|joint| joint.name == name) {
+            return Err(AddJointError::DuplicateName { joint });
+        }
+        if let Some(&joint) = bus_joints
+            .iter()
+            .find(|&&joint| self.joints[joint].motor_id == motor_id)
+        {
+            return Err(AddJointError::DuplicateMotor { joint });
+        }
+        let joint = self.joints.len();
+        bus_joints.push(joint);
+        self.joints.push(Joint { name, bus, motor_id });
+        Ok(joint)
+    }
+
+    /// Get the joints, in the order they were added.
+    pub fn joints(&self) -> &[Joint] {
+        &self.joints
+    }
+
+    /// Get the index of the joint with the given name.
+    pub fn joint_index(&self, name: &str) -> Option<usize> {
+        self.joints.iter().position(|joint| joint.name == name)
+    }
+
+    /// Get the bus and motor ID of a joint.
+    pub fn joint_location(&self, joint: usize) -> Option<(usize, u8)> {
+        self.joints.get(joint).map(|joint| (joint.bus, joint.motor_id))
+    }
+
+    /// Get a mutable reference to a bus, for example to talk to a single motor.
+    pub fn bus(&mut self, bus: usize) -> Option<&mut Bus<SerialPort, Buffer>> {
+        self.buses.get_mut(bus)
+    }
+
+    /// Get mutable references to all buses.
+    pub fn buses(&mut self) -> &mut [Bus<SerialPort, Buffer>] {
+        &mut self.buses
+    }
+
+    /// Get the buses back.
+    pub fn into_buses(self) -> Vec<Bus<SerialPort, Buffer>> {
+        self.buses
+    }
+
+    /// Bulk read and write status registers across every joint, running all buses concurrently.
+    ///
+    /// Like [`Bus::bulk_read_write_split`], but `rows` holds the encoded write data of every joint, in joint order:
+    /// `write_registers.len() * 4` bytes per joint. When `write_registers` is empty, `rows` is ignored and may be
+    /// empty. The returned `Vec` holds one read reply per joint in joint order, and is empty when `read_registers`
+    /// is empty.
+    ///
+    /// Every bus finishes its transfer even if another bus fails. If any bus fails as a whole, the error of the
+    /// first such bus is returned.
+    pub async fn bulk_read_write<T: AsRef<[u8]>>(
+        &mut self,
+        rows: &[T],
+        read_registers: &[StatusRegister],
+        write_registers: &[StatusRegister],
+    ) -> Result<JointReplies<SerialPort::Error>, BusSetError<SerialPort::Error>> {
+        if !write_registers.is_empty() && rows.len() != self.joints.len() {
+            return Err(BusSetError::DeviceCount(DeviceCountError {
+                expected: self.joints.len(),
+                actual: rows.len(),
+            }));
+        }
+
+        let joints = &self.joints;
+        let transfers = self.buses.iter_mut().zip(&self.bus_joints).map(|(bus, bus_joints)| {
+            let devices: Vec<_> = bus_joints
+                .iter()
+                .map(|&joint| BulkWriteData {
+                    motor_id: joints[joint].motor_id,
+                    data: rows.get(joint).map_or(&[][..], AsRef::as_ref),
+                })
+                .collect();
+            async move {
+                if devices.is_empty() {
+                    return Ok(Vec::new());
+                }
+                bus.bulk_read_write_split(&devices, read_registers, write_registers)
+                    .await
+            }
+        });
+        let results = join_all(transfers.collect()).await;
+
+        let mut replies: Vec<_> = self.joints.iter().map(|_| None).collect();
+        let mut error = None;
+        for (bus, (result, bus_joints)) in results.into_iter().zip(&self.bus_joints).enumerate() {
+            match result {
+                Ok(bus_replies) => {
+                    for (&joint, reply) in bus_joints.iter().zip(bus_replies) {
+                        replies[joint] = Some(reply);
+                    }
+                },
+                Err(source) => {
+                    error.get_or_insert(BusSetError::Bus { bus, source });
+                },
+            }
+        }
+        if let Some(error) = error {
+            return Err(error);
+        }
+        Ok(replies.into_iter().flatten().collect())
+    }
+
+    /// Bulk read status registers from every joint, running all buses concurrently.
+    ///
+    /// See [`BusSet::bulk_read_write`].
+    pub async fn bulk_read(
+        &mut self,
+        read_registers: &[StatusRegister],
+    ) -> Result<JointReplies<SerialPort::Error>, BusSetError<SerialPort::Error>> {
+        self.bulk_read_write::<&[u8]>(&[], read_registers, &[]).await
+    }
+
+    /// Bulk write status registers to every joint, running all buses concurrently.
+    ///
+    /// See [`BusSet::bulk_read_write`].
+    pub async fn bulk_write<T: AsRef<[u8]>>(
+        &mut self,
+        rows: &[T],
+        write_registers: &[StatusRegister],
+    ) -> Result<(), BusSetError<SerialPort::Error>> {
+        self.bulk_read_write(rows, &[], write_registers).await?;
+        Ok(())
+    }
+
+    /// Bulk read a [`RegisterSet`] from every joint, decoding each reply.
+    ///
+    /// See [`Bus::bulk_read_typed`] and [`BusSet::bulk_read_write`].
+    pub async fn bulk_read_typed<R: RegisterSet>(
+        &mut self,
+    ) -> Result<TypedJointReplies<R, SerialPort::Error>, BusSetError<SerialPort::Error>> {
+        let replies = self.bulk_read(R::REGISTERS).await?;
+        Ok(replies
+            .into_iter()
+            .map(|reply| -> Result<_, ReadError<SerialPort::Error>> {
+                let reply = reply?;
+                Ok(Response {
+                    motor_id: reply.motor_id,
+                    warning: reply.warning,
+                    data: R::decode(&reply.data)?,
+                })
+            })
+            .collect())
+    }
+
+    /// Bulk write a [`WritableRegisterSet`] to every joint, with one tuple of values per joint in joint order.
+    ///
+    /// See [`Bus::bulk_write_typed`] and [`BusSet::bulk_read_write`].
+    pub async fn bulk_write_typed<R: WritableRegisterSet>(
+        &mut self,
+        values: impl IntoIterator<Item = R::Values>,
+    ) -> Result<(), BusSetError<SerialPort::Error>> {
+        let row_len = R::REGISTERS.len() * REGISTER_BYTES;
+        let rows: Vec<Vec<u8>> = values
+            .into_iter()
+            .map(|values| {
+                let mut row = alloc::vec![0; row_len];
+                let encoded = R::encode(values, &mut row);
+                debug_assert!(encoded.is_ok(), "the row holds every register");
+                row
+            })
+            .collect();
+        self.bulk_write(&rows, R::REGISTERS).await
+    }
+}
+
+impl<SerialPort, Buffer> core::fmt::Debug for BusSet<SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort + core::fmt::Debug,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BusSet")
+            .field("buses", &self.buses)
+            .field("joints", &self.joints)
+            .finish()
+    }
+}
+
+/// Run futures concurrently on the current task, and return their outputs in order.
+async fn join_all<F: Future>(futures: Vec<F>) -> Vec<F::Output> {
+    let mut futures: Vec<_> = futures.into_iter().map(Box::pin).collect();
+    let mut outputs: Vec<Option<F::Output>> = futures.iter().map(|_| None).collect();
+    core::future::poll_fn(|cx| {
+        let mut pending = false;
+        for (future, output) in futures.iter_mut().zip(&mut outputs) {
+            if output.is_none() {
+                match future.as_mut().poll(cx) {
+                    Poll::Ready(value) => *output = Some(value),
+                    Poll::Pending => pending = true,
+                }
+            }
+        }
+        if pending { Poll::Pending } else { Poll::Ready(()) }
+    })
+    .await;
+    outputs.into_iter().flatten().collect()
+}
@@ -1,5 +1,6 @@
 //! The error types from communcication errors and motor error states
 
+use crate::{ConfigRegister, ErrorFlags, ValueType};
 use core::fmt::{Display, Formatter, Result as FmtResult};
 use derive_more::{Display, Error, From};
 
@@ -12,10 +13,229 @@ pub enum TransferError<E> {
     WriteError(WriteError<E>),
 
     /// The read failed.
-    #[from(ReadError<E>, InvalidMessage, InvalidPacketId)]
+    #[from(ReadError<E>, InvalidMessage, InvalidPacketId, MotorError)]
     ReadError(ReadError<E>),
 }
 
+/// An error that can occur while scanning the bus at several baud rates.
+#[derive(Debug, Display, Error, From)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ScanError<E> {
+    /// Failed to change the baud rate of the serial port.
+    #[from(skip)]
+    SetBaudRate(E),
+
+    /// A ping failed for another reason than a timeout.
+    #[from(TransferError<E>, WriteError<E>, ReadError<E>)]
+    Transfer(TransferError<E>),
+}
+
+/// An error that can occur while changing the ID of a motor with [`crate::Bus::change_id`].
+///
+/// Each variant names the step that failed.
+#[derive(Debug, Display, Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChangeIdError<E> {
+    /// The old or new ID is the broadcast ID.
+    #[display("motor ID {motor_id:#04X} is reserved")]
+    InvalidId {
+        /// The requested ID.
+        motor_id: u8,
+    },
+
+    /// Another motor already answers to the new ID.
+    #[display("motor ID {motor_id:#04X} is already in use")]
+    IdInUse {
+        /// The requested ID.
+        motor_id: u8,
+    },
+
+    /// Failed to check whether the new ID is free.
+    #[display("failed to check whether the new ID is free: {_0}")]
+    CheckFree(TransferError<E>),
+
+    /// Failed to write the new ID.
+    #[display("failed to write the new ID: {_0}")]
+    Write(TransferError<E>),
+
+    /// The motor did not answer with the new ID after the write, for example because torque is enabled.
+    #[display("motor {motor_id:#04X} did not take the new ID")]
+    Rejected {
+        /// The ID of the motor.
+        motor_id: u8,
+    },
+
+    /// Failed to save the config at the new ID.
+    #[display("failed to save the config at the new ID: {_0}")]
+    Save(TransferError<E>),
+
+    /// The motor did not answer a ping at the new ID.
+    #[display("the motor did not answer at the new ID: {_0}")]
+    Verify(TransferError<E>),
+}
+
+/// An error that can occur while changing the baud rate of a motor with [`crate::Bus::change_baud_rate`].
+///
+/// Each variant names the step that failed.
+#[derive(Debug, Display, Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChangeBaudRateError<E> {
+    /// The motor ID is the broadcast ID.
+    #[display("motor ID {motor_id:#04X} is reserved")]
+    InvalidId {
+        /// The requested ID.
+        motor_id: u8,
+    },
+
+    /// Failed to write the new baud rate to the motor.
+    #[display("failed to write the new baud rate: {_0}")]
+    Write(TransferError<E>),
+
+    /// The motor did not report the new baud rate after the write, for example because torque is enabled.
+    #[display("motor {motor_id:#04X} did not take the new baud rate")]
+    Rejected {
+        /// The ID of the motor.
+        motor_id: u8,
+    },
+
+    /// Failed to switch the serial port to the new baud rate.
+    #[display("failed to switch the serial port to the new baud rate")]
+    SetBaudRate(E),
+
+    /// Failed to save the config at the new baud rate.
+    #[display("failed to save the config at the new baud rate: {_0}")]
+    Save(TransferError<E>),
+
+    /// The motor did not answer a ping at the new baud rate.
+    #[display("the motor did not answer at the new baud rate: {_0}")]
+    Verify(TransferError<E>),
+}
+
+/// An error that can occur while applying a config to a motor with [`crate::Bus::apply_config`].
+///
+/// Each variant names the step that failed.
+#[derive(Debug, Display, Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ApplyConfigError<E> {
+    /// The ID in the config can not be assigned to a motor.
+    #[display("invalid motor ID in the config: {id:#X}")]
+    InvalidId {
+        /// The ID in the config.
+        id: u32,
+    },
+
+    /// Torque is not disabled, so the motor would ignore the config writes.
+    #[display("torque of motor {motor_id:#04X} is not disabled")]
+    TorqueEnabled {
+        /// The ID of the motor.
+        motor_id: u8,
+    },
+
+    /// Another motor already answers at the ID in the config.
+    #[display("motor ID {motor_id:#04X} is already in use")]
+    IdInUse {
+        /// The ID in the config.
+        motor_id: u8,
+    },
+
+    /// Failed to check whether the ID in the config is free.
+    #[display("failed to check whether the new ID is free: {_0}")]
+    CheckFree(TransferError<E>),
+
+    /// Failed to read the current config of the motor.
+    #[display("failed to read the current config: {_0}")]
+    Read(TransferError<E>),
+
+    /// Failed to write a register.
+    #[display("failed to write {register}: {source}")]
+    Write {
+        /// The register that could not be written.
+        register: ConfigRegister,
+        /// The error that occurred.
+        source: TransferError<E>,
+    },
+
+    /// Failed to switch the serial port to the new baud rate.
+    #[display("failed to switch the serial port to the new baud rate")]
+    SetBaudRate(E),
+
+    /// Failed to save the config.
+    #[display("failed to save the config: {_0}")]
+    Save(TransferError<E>),
+
+    /// Failed to read back the config.
+    #[display("failed to read back the config: {_0}")]
+    Verify(TransferError<E>),
+
+    /// A register read back a different value than was written.
+    #[display("{register} does not hold the applied value")]
+    Mismatch {
+        /// The register that does not match.
+        register: ConfigRegister,
+    },
+}
+
+/// A joint can not be added to a [`crate::asynchronous::BusSet`].
+#[derive(Debug, Clone, Eq, PartialEq, Display, Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AddJointError {
+    /// The bus index is out of range.
+    #[display("there is no bus {bus}")]
+    UnknownBus {
+        /// The bus index.
+        bus: usize,
+    },
+
+    /// Another joint already has the name.
+    #[display("joint {joint} already has this name")]
+    DuplicateName {
+        /// The index of the joint with the name.
+        joint: usize,
+    },
+
+    /// Another joint already addresses the motor on the bus.
+    #[display("joint {joint} already addresses this motor")]
+    DuplicateMotor {
+        /// The index of the joint addressing the motor.
+        joint: usize,
+    },
+}
+
+/// An error that can occur during a bulk transfer on a [`crate::asynchronous::BusSet`].
+///
+/// Errors affecting the reply of a single joint are returned in that joint's slot instead.
+#[derive(Debug, Display, Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BusSetError<E> {
+    /// The data given does not have one row per joint.
+    #[display("{_0}")]
+    DeviceCount(DeviceCountError),
+
+    /// A bulk transfer failed as a whole on one of the buses.
+    #[display("bulk transfer on bus {bus} failed: {source}")]
+    Bus {
+        /// The index of the bus.
+        bus: usize,
+        /// The error that occurred.
+        source: TransferError<E>,
+    },
+}
+
+/// An error that stops [`crate::Bus::monitor`].
+#[derive(Debug, Display, Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MonitorError<E, S> {
+    /// A bulk read failed as a whole, for example because the packet could not be sent.
+    ///
+    /// Errors affecting the reply of a single motor are handed to the sink instead.
+    #[display("bulk read failed: {_0}")]
+    Transfer(TransferError<E>),
+
+    /// The sink failed to record a sample.
+    #[display("failed to record a sample")]
+    Sink(#[error(not(source))] S),
+}
+
 /// An error that can occur during a write transfer.
 #[derive(Debug, Display, Error, From)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -26,6 +246,12 @@ pub enum WriteError<E> {
     /// A bulk request asked for more registers than the wire format can encode.
     TooManyRegisters(TooManyRegistersError),
 
+    /// The value can not be written to the register.
+    InvalidValue(InvalidValueError),
+
+    /// A [`crate::BulkPlan`] was given data for a different number of motors than it was built for.
+    DeviceCount(DeviceCountError),
+
     /// Failed to discard the input buffer before writing the instruction.
     #[from(skip)]
     DiscardBuffer(E),
@@ -35,6 +261,38 @@ pub enum WriteError<E> {
     Write(E),
 }
 
+/// A value can not be written to a register with [`crate::Bus::write_value`].
+#[derive(Debug, Clone, Eq, PartialEq, Display, Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InvalidValueError {
+    /// The value does not have the type of the register.
+    #[display("register {register} holds a {expected:?} value, got a {actual:?} value")]
+    TypeMismatch {
+        /// The name of the register.
+        register: &'static str,
+        /// The type of the register.
+        expected: ValueType,
+        /// The type of the value.
+        actual: ValueType,
+    },
+
+    /// The register can only be read.
+    #[display("register {register} is read-only")]
+    ReadOnly {
+        /// The name of the register.
+        register: &'static str,
+    },
+
+    /// The value is not one of the values the register can hold, such as an unknown [`crate::OperatingMode`].
+    #[display("register {register} can not hold the value {value}")]
+    UnknownValue {
+        /// The name of the register.
+        register: &'static str,
+        /// The raw value.
+        value: u32,
+    },
+}
+
 /// A bulk request specified more registers than the wire format can encode.
 ///
 /// The bulk packet packs the read and write register counts into a single byte,
@@ -61,6 +319,19 @@ impl TooManyRegistersError {
     }
 }
 
+/// A bulk transfer was given data for a different number of motors than it addresses,
+/// see [`crate::BulkPlan`] and [`crate::asynchronous::BusSet`].
+#[derive(Debug, Display, Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[display("expected data for {} motors, got {}", self.expected, self.actual)]
+pub struct DeviceCountError {
+    /// The number of motors addressed.
+    pub expected: usize,
+
+    /// The number of rows of data given.
+    pub actual: usize,
+}
+
 /// The buffer is too small to hold the entire message.
 ///
 /// Consider increasing the size of the buffer.
@@ -89,8 +360,32 @@ pub enum ReadError<E> {
     Io(E),
 
     /// The received message is invalid.
-    #[from(InvalidMessage, InvalidChecksum, InvalidPacketId, InvalidParameterCount)]
+    #[from(
+        InvalidMessage,
+        InvalidChecksum,
+        InvalidPacketId,
+        InvalidParameterCount,
+        InvalidRegisterValue
+    )]
     InvalidMessage(InvalidMessage),
+
+    /// The motor reported an error in its response.
+    #[from]
+    MotorError(MotorError),
+}
+
+/// The motor reported one or more [`crate::ERROR_FLAGS`] in the error byte of its response.
+///
+/// Whether this is treated as an error is controlled by the [`crate::ErrorPolicy`] of the bus.
+#[derive(Debug, Clone, Eq, PartialEq, Display, Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[display("motor {:#04X} reported errors: {}", self.motor_id, self.flags)]
+pub struct MotorError {
+    /// The motor that sent the response.
+    pub motor_id: u8,
+
+    /// All flags from the error byte of the response, including any [`crate::WARNING_FLAGS`].
+    pub flags: ErrorFlags,
 }
 
 /// The received message is not valid.
@@ -105,6 +400,9 @@ pub enum InvalidMessage {
 
     /// The message has an invalid parameter count.
     InvalidParameterCount(InvalidParameterCount),
+
+    /// The message contains a register value that is not valid for the register's type.
+    InvalidRegisterValue(InvalidRegisterValue),
 }
 
 /// The received message has an invalid checksum value.
@@ -119,6 +417,18 @@ pub struct InvalidChecksum {
     pub computed: u8,
 }
 
+/// The received register value is not valid for the register's type.
+#[derive(Debug, Clone, Eq, PartialEq, Display, Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[display("invalid {} value: {:#X}", self.type_name, self.value)]
+pub struct InvalidRegisterValue {
+    /// The name of the type the value was decoded into.
+    pub type_name: &'static str,
+
+    /// The raw register value.
+    pub value: u32,
+}
+
 /// The received message has an invalid or unexpected packet ID.
 #[derive(Debug, Clone, Eq, PartialEq, Error)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -194,6 +504,17 @@ impl BufferTooSmallError {
     }
 }
 
+impl MotorError {
+    /// Check if the flags contain any [`crate::ERROR_FLAGS`].
+    pub fn check(motor_id: u8, flags: ErrorFlags) -> Result<(), Self> {
+        if flags.intersects(crate::ERROR_FLAGS) {
+            Err(Self { motor_id, flags })
+        } else {
+            Ok(())
+        }
+    }
+}
+
 impl InvalidPacketId {
     /// Check if the packet ID matches the expected value.
     pub fn check(actual: u8, expected: u8) -> Result<(), Self> {
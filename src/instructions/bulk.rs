@@ -15,25 +15,14 @@ use crate::error::{
     BufferTooSmallError, InvalidPacketId, InvalidParameterCount, ReadError, TooManyRegistersError, TransferError,
     WriteError,
 };
-use crate::protocol::Response;
+use crate::protocol::{
+    BROADCAST_ID, MAX_BULK_REGISTERS, PACKET_PARAMS_START, REGISTER_BYTES, REPLY_FRAMING_BYTES, Response,
+};
 use crate::{BulkWriteData, Instruction, StatusRegister};
 
-/// Broadcast ID used to address all motors with a bulk packet.
-const BROADCAST_ID: u8 = 0xFE;
-
-/// Byte offset of the parameter section within a written packet: `FF FF`, id, len, instruction.
-const PACKET_PARAMS_START: usize = 5;
-
-/// Bytes per register value on the wire (4 little-endian bytes).
-const REGISTER_BYTES: usize = 4;
-
-/// Maximum registers per direction in a bulk packet. The read and write counts
-/// share one byte (a 4-bit nibble each), so each direction supports at most 15.
-const MAX_BULK_REGISTERS: usize = 0x0F;
-
-/// Non-payload bytes in a status reply, added to the read length to estimate the
-/// reply's on-wire size for the read timeout.
-const REPLY_FRAMING_BYTES: usize = 3;
+/// One owned reply per motor, as returned by the `_alloc` bulk helpers.
+#[cfg(feature = "alloc")]
+pub(crate) type OwnedReplies<E> = alloc::vec::Vec<Result<Response<alloc::vec::Vec<u8>>, ReadError<E>>>;
 
 #[super::super::bisync]
 impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
@@ -190,10 +179,7 @@ where
         &mut self,
         motor_ids: &[u8],
         read_registers: &[StatusRegister],
-    ) -> Result<
-        alloc::vec::Vec<Result<Response<alloc::vec::Vec<u8>>, ReadError<SerialPort::Error>>>,
-        TransferError<SerialPort::Error>,
-    > {
+    ) -> Result<OwnedReplies<SerialPort::Error>, TransferError<SerialPort::Error>> {
         let mut results = alloc::vec::Vec::with_capacity(motor_ids.len());
         self.bulk_read(motor_ids, read_registers, |response| {
             results.push(response.map(|response| Response {
@@ -217,10 +203,7 @@ where
         devices: Iter,
         read_registers: &[StatusRegister],
         write_registers: &[StatusRegister],
-    ) -> Result<
-        alloc::vec::Vec<Result<Response<alloc::vec::Vec<u8>>, ReadError<SerialPort::Error>>>,
-        TransferError<SerialPort::Error>,
-    >
+    ) -> Result<OwnedReplies<SerialPort::Error>, TransferError<SerialPort::Error>>
     where
         Iter: IntoIterator<Item = Data>,
         Iter::IntoIter: ExactSizeIterator,
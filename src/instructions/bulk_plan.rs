@@ -0,0 +1,174 @@
+//! Bulk transfers prepared once and repeated every control cycle, see [`BulkPlan`].
+//!
+//! [`Bus::bulk_read_write`] encodes the whole packet and recomputes the reply lengths on every call.
+//! For a fixed set of motors and registers that work only needs to happen once: [`Bus::bulk_plan`] does it
+//! up front, and [`Bus::bulk_execute`] patches the write data and checksum into the packet already held in the
+//! write buffer. Any other transfer overwrites the write buffer, after which the next cycle copies the packet
+//! back from the plan.
+
+use super::super::Bus;
+use crate::bus::message_transfer_time;
+use crate::checksum;
+use crate::error::{
+    BufferTooSmallError, DeviceCountError, InvalidPacketId, InvalidParameterCount, ReadError, TooManyRegistersError,
+    TransferError, WriteError,
+};
+use crate::protocol::{
+    BROADCAST_ID, MAX_BULK_REGISTERS, MAX_PACKET_LEN, PACKET_PARAMS_START, REGISTER_BYTES, REPLY_FRAMING_BYTES,
+    Response,
+};
+use crate::{BulkPlan, Instruction, StatusRegister};
+
+#[super::super::bisync]
+impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
+where
+    SerialPort: super::super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Prepare a bulk transfer of the same registers to and from the same motors, to be sent with [`Bus::bulk_execute`].
+    ///
+    /// This checks the register counts and that the packet fits the write buffer, and encodes everything but the
+    /// write data. Nothing is sent. See [`Bus::bulk_read_write`] for the meaning of the arguments.
+    pub fn bulk_plan(
+        &mut self,
+        motor_ids: &[u8],
+        read_registers: &[StatusRegister],
+        write_registers: &[StatusRegister],
+    ) -> Result<BulkPlan, WriteError<SerialPort::Error>> {
+        let motor_count = motor_ids.len();
+        let read_count = read_registers.len();
+        let write_count = write_registers.len();
+        let write_len = write_count * REGISTER_BYTES;
+        TooManyRegistersError::check(read_count, MAX_BULK_REGISTERS)?;
+        TooManyRegistersError::check(write_count, MAX_BULK_REGISTERS)?;
+
+        let parameter_count = 2 + read_count + write_count + motor_count * (1 + write_len);
+        let mut packet = [0; MAX_PACKET_LEN];
+        let packet_len = Self::make_packet(
+            &mut packet,
+            BROADCAST_ID,
+            Instruction::BulkComm as u8,
+            parameter_count,
+            |buffer| {
+                buffer[0] = motor_count as u8;
+                buffer[1] = ((read_count as u8) << 4) | (write_count as u8);
+                let registers = read_registers.iter().chain(write_registers);
+                for (byte, register) in buffer[2..].iter_mut().zip(registers) {
+                    *byte = *register as u8;
+                }
+                let rows = buffer[2 + read_count + write_count..].chunks_mut(1 + write_len);
+                for (row, &motor_id) in rows.zip(motor_ids) {
+                    row[0] = motor_id;
+                }
+                Ok(())
+            },
+        )?;
+        BufferTooSmallError::check(packet_len, self.write_buffer.as_ref().len())?;
+
+        let read_len = read_count * REGISTER_BYTES;
+        let reply_timeout = message_transfer_time((read_len + REPLY_FRAMING_BYTES) as u32, self.baud_rate)
+            + self.response_timeout_padding;
+        Ok(BulkPlan {
+            id: BulkPlan::next_id(),
+            packet,
+            packet_len,
+            motor_count,
+            first_id_index: PACKET_PARAMS_START + 2 + read_count + write_count,
+            write_len,
+            read_len,
+            reply_timeout,
+        })
+    }
+
+    /// Send a bulk transfer prepared by [`Bus::bulk_plan`].
+    ///
+    /// `rows` yields the encoded write data for each motor, in the order of [`BulkPlan::motor_ids`]:
+    /// [`BulkPlan::row_len`] bytes per motor. When the plan writes no registers, `rows` is ignored and may be empty.
+    /// See [`Bus::bulk_read_write`] for the meaning of the `on_response` callback.
+    pub async fn bulk_execute<Rows, T, F>(
+        &mut self,
+        plan: &BulkPlan,
+        rows: Rows,
+        mut on_response: F,
+    ) -> Result<(), TransferError<SerialPort::Error>>
+    where
+        Rows: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+        F: FnMut(Result<Response<&[u8]>, ReadError<SerialPort::Error>>),
+    {
+        if self.stamped_plan != Some(plan.id) {
+            let packet = &plan.packet[..plan.packet_len];
+            BufferTooSmallError::check(packet.len(), self.write_buffer.as_ref().len()).map_err(WriteError::from)?;
+            self.write_buffer.as_mut()[..packet.len()].copy_from_slice(packet);
+            self.stamped_plan = Some(plan.id);
+        }
+
+        if plan.write_len > 0 {
+            let stride = 1 + plan.write_len;
+            let buffer = &mut self.write_buffer.as_mut()[plan.first_id_index..][..plan.motor_count * stride];
+            let mut slots = buffer.chunks_mut(stride);
+            let mut count = 0;
+            for row in rows {
+                count += 1;
+                if let Some(slot) = slots.next() {
+                    let row = row.as_ref();
+                    BufferTooSmallError::check(plan.write_len, row.len()).map_err(WriteError::from)?;
+                    slot[1..].copy_from_slice(&row[..plan.write_len]);
+                }
+            }
+            if count != plan.motor_count {
+                return Err(WriteError::from(DeviceCountError {
+                    expected: plan.motor_count,
+                    actual: count,
+                })
+                .into());
+            }
+            let checksum_index = plan.packet_len - 1;
+            let buffer = self.write_buffer.as_mut();
+            buffer[checksum_index] = checksum::calculate_checksum(&buffer[2..checksum_index]);
+        }
+        self.send_packet(plan.packet_len).await?;
+
+        // Write-only bulk: the firmware sends no reply.
+        if plan.read_len == 0 {
+            return Ok(());
+        }
+        for expected_id in plan.motor_ids() {
+            let response = self
+                .read_response_timeout(plan.reply_timeout)
+                .await
+                .and_then(|response| {
+                    InvalidPacketId::check(response.motor_id, expected_id)?;
+                    InvalidParameterCount::check(response.data.len(), plan.read_len)?;
+                    Ok(response)
+                });
+            on_response(response);
+        }
+        Ok(())
+    }
+
+    /// Send a bulk transfer prepared by [`Bus::bulk_plan`], returning one read reply per motor as owned [`Vec`]s.
+    ///
+    /// Convenience wrapper around [`Bus::bulk_execute`] available with the `"alloc"` feature.
+    #[cfg(feature = "alloc")]
+    pub async fn bulk_execute_alloc<Rows, T>(
+        &mut self,
+        plan: &BulkPlan,
+        rows: Rows,
+    ) -> Result<super::bulk::OwnedReplies<SerialPort::Error>, TransferError<SerialPort::Error>>
+    where
+        Rows: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let mut results = alloc::vec::Vec::with_capacity(plan.motor_count);
+        self.bulk_execute(plan, rows, |response| {
+            results.push(response.map(|response| Response {
+                motor_id: response.motor_id,
+                warning: response.warning,
+                data: response.data.to_vec(),
+            }));
+        })
+        .await?;
+        Ok(results)
+    }
+}
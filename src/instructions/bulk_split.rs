@@ -0,0 +1,211 @@
+//! Bulk transfers too large for a single packet.
+//!
+//! A bulk packet holds at most 15 read and 15 write registers, and must fit both the write buffer and the
+//! packet's `u8` length field. [`Bus::bulk_read_write_split`] plans a grid of packets, splitting the registers
+//! into groups of at most 15 and the motors into groups that fit a packet, sends them back to back, and merges
+//! the replies per motor.
+
+use core::ops::Range;
+
+use super::super::Bus;
+#[cfg(feature = "alloc")]
+use crate::ErrorFlags;
+use crate::error::{BufferTooSmallError, TransferError, WriteError};
+#[cfg(feature = "alloc")]
+use crate::protocol::Response;
+use crate::protocol::{MAX_BULK_REGISTERS, REGISTER_BYTES};
+use crate::{BulkWriteData, StatusRegister};
+
+/// Bytes of a packet around its parameters: `FF FF`, id, len, instruction and checksum.
+const PACKET_OVERHEAD: usize = 6;
+
+/// The largest parameter count the length field can describe, which also counts the instruction and checksum.
+const MAX_PARAMETERS: usize = u8::MAX as usize - 2;
+
+/// One packet of a split bulk transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BulkChunk {
+    pub(crate) motors: Range<usize>,
+    pub(crate) reads: Range<usize>,
+    pub(crate) writes: Range<usize>,
+}
+
+/// Split a bulk transfer into packets that fit a write buffer of `buffer_len` bytes.
+///
+/// The packets are ordered by register group, then by motor group.
+pub(crate) fn plan_bulk(
+    motor_count: usize,
+    read_count: usize,
+    write_count: usize,
+    buffer_len: usize,
+) -> Result<impl Iterator<Item = BulkChunk>, BufferTooSmallError> {
+    let register_groups = read_count
+        .div_ceil(MAX_BULK_REGISTERS)
+        .max(write_count.div_ceil(MAX_BULK_REGISTERS))
+        .max(1);
+    let group = |count: usize, i: usize| (i * MAX_BULK_REGISTERS).min(count)..((i + 1) * MAX_BULK_REGISTERS).min(count);
+
+    // The widest register group needs the most room per motor, so size every motor group for it.
+    let reads = read_count.min(MAX_BULK_REGISTERS);
+    let writes = write_count.min(MAX_BULK_REGISTERS);
+    let fixed = 2 + reads + writes;
+    let per_motor = 1 + writes * REGISTER_BYTES;
+    let available = buffer_len.saturating_sub(PACKET_OVERHEAD).min(MAX_PARAMETERS);
+    let motors_per_packet = available.saturating_sub(fixed) / per_motor;
+    if motors_per_packet == 0 && motor_count > 0 {
+        return Err(BufferTooSmallError {
+            required_size: PACKET_OVERHEAD + fixed + per_motor,
+            total_size: buffer_len,
+        });
+    }
+    let motor_groups = motor_count.div_ceil(motors_per_packet.max(1));
+
+    Ok((0..register_groups).flat_map(move |r| {
+        (0..motor_groups).map(move |m| BulkChunk {
+            motors: m * motors_per_packet..((m + 1) * motors_per_packet).min(motor_count),
+            reads: group(read_count, r),
+            writes: group(write_count, r),
+        })
+    }))
+}
+
+#[super::super::bisync]
+impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
+where
+    SerialPort: super::super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Bulk read and write any number of status registers across any number of motors.
+    ///
+    /// Like [`Bus::bulk_read_write_alloc`], but requests with more than 15 registers in a direction, or with more
+    /// motors than fit the write buffer, are split into several packets sent back to back.
+    /// The returned `Vec` holds one entry per motor, in the order of `devices`, with the data of every
+    /// `read_registers` concatenated in order. The [`Response::warning`] combines the flags of all its replies,
+    /// and a motor is reported as an [`Err`] if any of its replies failed.
+    ///
+    /// The packets are not atomic: if one fails as a whole, the earlier packets have already been sent.
+    #[cfg(feature = "alloc")]
+    pub async fn bulk_read_write_split<Data, T>(
+        &mut self,
+        devices: &[Data],
+        read_registers: &[StatusRegister],
+        write_registers: &[StatusRegister],
+    ) -> Result<super::bulk::OwnedReplies<SerialPort::Error>, TransferError<SerialPort::Error>>
+    where
+        Data: AsRef<BulkWriteData<T>>,
+        T: AsRef<[u8]>,
+    {
+        let write_len = write_registers.len() * REGISTER_BYTES;
+        for device in devices {
+            BufferTooSmallError::check(write_len, device.as_ref().data.as_ref().len()).map_err(WriteError::from)?;
+        }
+        let chunks = plan_bulk(
+            devices.len(),
+            read_registers.len(),
+            write_registers.len(),
+            self.write_buffer.as_ref().len(),
+        )
+        .map_err(WriteError::from)?;
+
+        let mut results: super::bulk::OwnedReplies<SerialPort::Error> = if read_registers.is_empty() {
+            alloc::vec::Vec::new()
+        } else {
+            devices
+                .iter()
+                .map(|device| {
+                    Ok(Response {
+                        motor_id: device.as_ref().motor_id,
+                        warning: ErrorFlags::empty(),
+                        data: alloc::vec::Vec::with_capacity(read_registers.len() * REGISTER_BYTES),
+                    })
+                })
+                .collect()
+        };
+
+        for chunk in chunks {
+            let writes = chunk.writes.start * REGISTER_BYTES..chunk.writes.end * REGISTER_BYTES;
+            let chunk_devices = devices[chunk.motors.clone()].iter().map(|device| {
+                let device = device.as_ref();
+                BulkWriteData {
+                    motor_id: device.motor_id,
+                    data: &device.data.as_ref()[writes.clone()],
+                }
+            });
+            let mut slots = results.get_mut(chunk.motors.clone()).unwrap_or_default().iter_mut();
+            self.bulk_read_write(
+                chunk_devices,
+                &read_registers[chunk.reads],
+                &write_registers[chunk.writes],
+                |reply| {
+                    let Some(slot) = slots.next() else { return };
+                    match (slot.as_mut(), reply) {
+                        (Ok(merged), Ok(reply)) => {
+                            merged.warning |= reply.warning;
+                            merged.data.extend_from_slice(reply.data);
+                        },
+                        (Ok(_), Err(e)) => *slot = Err(e),
+                        (Err(_), _) => (),
+                    }
+                },
+            )
+            .await?;
+        }
+        Ok(results)
+    }
+
+    /// Bulk read any number of status registers from any number of motors.
+    ///
+    /// See [`Bus::bulk_read_write_split`].
+    #[cfg(feature = "alloc")]
+    pub async fn bulk_read_split(
+        &mut self,
+        motor_ids: &[u8],
+        read_registers: &[StatusRegister],
+    ) -> Result<super::bulk::OwnedReplies<SerialPort::Error>, TransferError<SerialPort::Error>> {
+        let devices: alloc::vec::Vec<_> = motor_ids
+            .iter()
+            .map(|&motor_id| BulkWriteData {
+                motor_id,
+                data: &[][..],
+            })
+            .collect();
+        self.bulk_read_write_split(&devices, read_registers, &[]).await
+    }
+
+    /// Bulk write any number of status registers to any number of motors.
+    ///
+    /// Like [`Bus::bulk_write`], but split into several packets when needed, see [`Bus::bulk_read_write_split`].
+    pub async fn bulk_write_split<Data, T>(
+        &mut self,
+        devices: &[Data],
+        write_registers: &[StatusRegister],
+    ) -> Result<(), TransferError<SerialPort::Error>>
+    where
+        Data: AsRef<BulkWriteData<T>>,
+        T: AsRef<[u8]>,
+    {
+        let write_len = write_registers.len() * REGISTER_BYTES;
+        for device in devices {
+            BufferTooSmallError::check(write_len, device.as_ref().data.as_ref().len()).map_err(WriteError::from)?;
+        }
+        let chunks = plan_bulk(
+            devices.len(),
+            0,
+            write_registers.len(),
+            self.write_buffer.as_ref().len(),
+        )
+        .map_err(WriteError::from)?;
+        for chunk in chunks {
+            let writes = chunk.writes.start * REGISTER_BYTES..chunk.writes.end * REGISTER_BYTES;
+            let chunk_devices = devices[chunk.motors].iter().map(|device| {
+                let device = device.as_ref();
+                BulkWriteData {
+                    motor_id: device.motor_id,
+                    data: &device.data.as_ref()[writes.clone()],
+                }
+            });
+            self.bulk_write(chunk_devices, &write_registers[chunk.writes]).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+//! Bulk transfers of registers given as types, see [`RegisterSet`].
+
+use super::super::Bus;
+use crate::BulkWriteData;
+use crate::error::{ReadError, TooManyRegistersError, TransferError, WriteError};
+use crate::protocol::{REGISTER_BYTES, Response};
+use crate::registers::{RegisterSet, WritableRegisterSet};
+
+/// The most registers in a [`WritableRegisterSet`].
+const MAX_SET_REGISTERS: usize = 12;
+
+/// One decoded reply per motor, as returned by [`Bus::bulk_read_typed_alloc`].
+#[cfg(feature = "alloc")]
+type TypedReplies<R, E> = alloc::vec::Vec<Result<Response<<R as RegisterSet>::Values>, ReadError<E>>>;
+
+#[super::super::bisync]
+impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
+where
+    SerialPort: super::super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Bulk read a [`RegisterSet`] from multiple motors in a single packet, decoding each reply.
+    ///
+    /// The registers are given as a tuple of register types, ie `Bus::bulk_read_typed::<(status::PresentPos, status::PresentVel)>`,
+    /// and each reply is decoded into a tuple of their values, such as `(f32, f32)`.
+    /// See [`Bus::bulk_read_write`] for the meaning of the `on_response` callback.
+    pub async fn bulk_read_typed<R: RegisterSet>(
+        &mut self,
+        motor_ids: &[u8],
+        mut on_response: impl FnMut(Result<Response<R::Values>, ReadError<SerialPort::Error>>),
+    ) -> Result<(), TransferError<SerialPort::Error>> {
+        self.bulk_read(motor_ids, R::REGISTERS, |response| {
+            on_response(response.and_then(|response| {
+                Ok(Response {
+                    motor_id: response.motor_id,
+                    warning: response.warning,
+                    data: R::decode(response.data)?,
+                })
+            }))
+        })
+        .await
+    }
+
+    /// Bulk read a [`RegisterSet`] from multiple motors, returning one decoded reply per motor.
+    ///
+    /// Convenience wrapper around [`Bus::bulk_read_typed`] available with the `"alloc"` feature.
+    #[cfg(feature = "alloc")]
+    pub async fn bulk_read_typed_alloc<R: RegisterSet>(
+        &mut self,
+        motor_ids: &[u8],
+    ) -> Result<TypedReplies<R, SerialPort::Error>, TransferError<SerialPort::Error>> {
+        let mut results = alloc::vec::Vec::with_capacity(motor_ids.len());
+        self.bulk_read_typed::<R>(motor_ids, |response| results.push(response))
+            .await?;
+        Ok(results)
+    }
+
+    /// Bulk write a [`WritableRegisterSet`] to multiple motors in a single packet.
+    ///
+    /// The registers are given as a tuple of register types, ie `Bus::bulk_write_typed::<(status::GoalPos, status::GoalVel)>`,
+    /// and `devices` yields a motor ID and a tuple of values for each motor, such as `(1, (0.5, 0.0))`.
+    /// No reply is expected from the motors.
+    pub async fn bulk_write_typed<R: WritableRegisterSet>(
+        &mut self,
+        devices: impl IntoIterator<Item = (u8, R::Values), IntoIter: ExactSizeIterator>,
+    ) -> Result<(), TransferError<SerialPort::Error>> {
+        TooManyRegistersError::check(R::REGISTERS.len(), MAX_SET_REGISTERS).map_err(WriteError::from)?;
+        let devices = devices.into_iter().map(|(motor_id, values)| {
+            let mut data = [0; MAX_SET_REGISTERS * REGISTER_BYTES];
+            let encoded = R::encode(values, &mut data);
+            debug_assert!(encoded.is_ok(), "the row was checked to fit every register");
+            BulkWriteData { motor_id, data }
+        });
+        self.bulk_write(devices, R::REGISTERS).await
+    }
+}
@@ -1,6 +1,14 @@
 mod bulk;
+mod bulk_plan;
+mod bulk_split;
+mod bulk_typed;
+mod monitor;
+mod motor_config;
 mod ping;
 mod read;
+mod reassign;
 mod save_config;
+mod scan;
 mod write;
 mod set_abs_position;
+mod torque;
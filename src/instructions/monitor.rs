@@ -0,0 +1,96 @@
+use core::ops::Sub;
+use core::time::Duration;
+
+use super::super::Bus;
+use crate::RegisterValue;
+use crate::error::{MonitorError, ReadError, TooManyRegistersError, WriteError};
+use crate::monitor::{MonitorConfig, Reading, Sample, TelemetrySink};
+use crate::protocol::MAX_BULK_REGISTERS;
+use log::debug;
+
+#[super::super::bisync]
+impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
+where
+    SerialPort: super::super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Poll status registers from several motors at a fixed rate, and hand every reply to a sink.
+    ///
+    /// Each cycle is a single [`Self::bulk_read`]. Every motor reply, or the error in its place, is recorded as a
+    /// [`Sample`] timestamped with the start of the cycle. See [`crate::monitor`] for the available sinks.
+    ///
+    /// This runs until `config.cycles` have completed, the sink returns an error, or a bulk read fails as a whole.
+    /// Time is measured with the serial port's clock, so it also works with the simulated bus.
+    pub async fn monitor<S: TelemetrySink>(
+        &mut self,
+        config: &MonitorConfig<'_>,
+        sink: &mut S,
+    ) -> Result<(), MonitorError<SerialPort::Error, S::Error>>
+    where
+        SerialPort::Instant: Sub<Output = Duration>,
+        SerialPort::Error: core::error::Error + 'static,
+    {
+        TooManyRegistersError::check(config.registers.len(), MAX_BULK_REGISTERS)
+            .map_err(|e| MonitorError::Transfer(WriteError::from(e).into()))?;
+        let start = self.serial_port.make_deadline(Duration::ZERO);
+        let mut next_cycle = Duration::ZERO;
+        let mut values = [RegisterValue::U32(0); MAX_BULK_REGISTERS];
+        let mut cycle = 0;
+        while config.cycles.is_none_or(|cycles| cycle < cycles) {
+            let now = self.serial_port.make_deadline(Duration::ZERO) - start;
+            if now < next_cycle {
+                self.wait(next_cycle - now)
+                    .await
+                    .map_err(|e| MonitorError::Transfer(e.into()))?;
+            } else if cycle > 0 {
+                debug!("monitor cycle {} started {:?} late", cycle, now - next_cycle);
+            }
+            let timestamp = self.serial_port.make_deadline(Duration::ZERO) - start;
+
+            let mut motor_ids = config.motor_ids.iter();
+            let mut sink_result = Ok(());
+            self.bulk_read(config.motor_ids, config.registers, |reply| {
+                let Some(&motor_id) = motor_ids.next() else { return };
+                if sink_result.is_err() {
+                    return;
+                }
+                let reply = reply.map(|response| {
+                    for ((value, register), bytes) in values
+                        .iter_mut()
+                        .zip(config.registers)
+                        .zip(response.data.chunks_exact(4))
+                    {
+                        *value = RegisterValue::from_bytes(register.info().value_type, bytes.try_into().unwrap());
+                    }
+                    response.warning
+                });
+                let error: ReadError<SerialPort::Error>;
+                let reply = match reply {
+                    Ok(warning) => Ok(Reading {
+                        warning,
+                        values: &values[..config.registers.len()],
+                    }),
+                    Err(e) => {
+                        error = e;
+                        Err(&error as &dyn core::error::Error)
+                    },
+                };
+                sink_result = sink.record(&Sample {
+                    cycle,
+                    timestamp,
+                    motor_id,
+                    registers: config.registers,
+                    reply,
+                });
+            })
+            .await
+            .map_err(MonitorError::Transfer)?;
+            sink_result.map_err(MonitorError::Sink)?;
+
+            cycle += 1;
+            // A late cycle pushes the schedule back, so the next one still waits a full period.
+            next_cycle = core::cmp::max(next_cycle, timestamp) + config.period;
+        }
+        Ok(())
+    }
+}
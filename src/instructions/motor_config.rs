@@ -0,0 +1,301 @@
+//! Reading and applying a snapshot of every config register of a motor.
+//!
+//! A `ReadCfg` instruction may list several addresses, and the motor replies with all of their values at once,
+//! so a whole [`MotorConfig`] is read in a single round trip when the read buffer can hold the reply.
+//! [`Instruction::BulkComm`](crate::Instruction::BulkComm) only reaches the status table, so the configs of
+//! several motors are read with one `ReadCfg` request per motor.
+
+use super::super::Bus;
+use crate::bus::message_transfer_time;
+use crate::error::{
+    ApplyConfigError, BufferTooSmallError, InvalidMessage, InvalidPacketId, InvalidParameterCount, ReadError,
+    TransferError, WriteError,
+};
+use crate::protocol::{BROADCAST_ID, REGISTER_BYTES, REPLY_FRAMING_BYTES, Response};
+use crate::registers::{config, status};
+use crate::{ConfigRegister, ErrorFlags, MotorConfig, TorqueState};
+use log::{debug, warn};
+use strum::IntoEnumIterator;
+
+/// The number of config registers in a [`MotorConfig`].
+const CONFIG_COUNT: usize = config::REGISTERS.len();
+
+/// One config per motor, as returned by [`Bus::bulk_read_config_alloc`].
+#[cfg(feature = "alloc")]
+type ConfigReplies<E> = alloc::vec::Vec<Result<Response<MotorConfig>, ReadError<E>>>;
+
+#[super::super::bisync]
+impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
+where
+    SerialPort: super::super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Read every [`ConfigRegister`] of a motor.
+    ///
+    /// This takes a single `ReadCfg` transfer if the read buffer can hold every register, and several smaller
+    /// ones otherwise.
+    pub async fn read_all_config(&mut self, motor_id: u8) -> Result<MotorConfig, TransferError<SerialPort::Error>> {
+        Ok(self.read_config_snapshot(motor_id).await?.data)
+    }
+
+    /// Read every [`ConfigRegister`] of several motors.
+    ///
+    /// A `ReadCfg` request listing every config register is sent to each motor back to back, and the replies are
+    /// collected afterwards, so the motors answer without waiting for a round trip each. Pipelined replies are not
+    /// retried. If the read buffer can not hold a whole config, the motors are read one after the other
+    /// as with [`Bus::read_all_config`], and retried according to the [`crate::RetryPolicy`].
+    ///
+    /// `on_response` is called once per motor, in the order of `motor_ids`.
+    /// A motor that fails to reply, or whose reply can not be decoded, is delivered as an [`Err`] and the remaining
+    /// motors are still read. Failing to send a request aborts the whole read.
+    pub async fn bulk_read_config<F>(
+        &mut self,
+        motor_ids: &[u8],
+        mut on_response: F,
+    ) -> Result<(), TransferError<SerialPort::Error>>
+    where
+        F: FnMut(Result<Response<MotorConfig>, ReadError<SerialPort::Error>>),
+    {
+        const REPLY_LEN: usize = CONFIG_COUNT * REGISTER_BYTES;
+        if self.max_reply_parameters() < REPLY_LEN {
+            for &motor_id in motor_ids {
+                match self.read_config_snapshot(motor_id).await {
+                    Ok(response) => on_response(Ok(response)),
+                    Err(TransferError::ReadError(e)) => on_response(Err(e)),
+                    Err(e) => return Err(e),
+                }
+            }
+            return Ok(());
+        }
+
+        self.stamped_plan = None;
+        for (index, &motor_id) in motor_ids.iter().enumerate() {
+            let packet_len = Self::make_packet(
+                self.write_buffer.as_mut(),
+                motor_id,
+                ConfigRegister::READ_INST,
+                CONFIG_COUNT,
+                encode_config_addresses(0),
+            )
+            .map_err(WriteError::from)?;
+            // Only the first request may throw away stale input, the others must keep the replies already received.
+            if index == 0 {
+                self.send_packet(packet_len).await?;
+            } else {
+                self.queue_packet(packet_len).await?;
+            }
+        }
+
+        let timeout = message_transfer_time((REPLY_LEN + REPLY_FRAMING_BYTES) as u32, self.baud_rate)
+            + self.response_timeout_padding;
+        let mut index = 0;
+        while index < motor_ids.len() {
+            let response = self.read_response_timeout(timeout).await;
+            // A motor that does not reply is skipped over by the replies of the motors after it.
+            let (reply_index, reply_id) = match &response {
+                Ok(response) => match motor_ids[index..]
+                    .iter()
+                    .position(|&motor_id| motor_id == response.motor_id)
+                {
+                    Some(offset) => (index + offset, response.motor_id),
+                    // Not a reply to any of the remaining requests, so the current motor may still answer.
+                    None => {
+                        debug!("dropping a config reply from motor {:#04X}", response.motor_id);
+                        continue;
+                    },
+                },
+                Err(_) => (index, motor_ids[index]),
+            };
+            for &motor_id in &motor_ids[index..reply_index] {
+                on_response(Err(InvalidPacketId {
+                    actual: reply_id,
+                    expected: Some(motor_id),
+                }
+                .into()));
+            }
+            on_response(response.and_then(|response| decode_config(&response)));
+            index = reply_index + 1;
+        }
+        Ok(())
+    }
+
+    /// Read every [`ConfigRegister`] of several motors, returning one config per motor.
+    ///
+    /// Convenience wrapper around [`Bus::bulk_read_config`] available with the `"alloc"` feature.
+    #[cfg(feature = "alloc")]
+    pub async fn bulk_read_config_alloc(
+        &mut self,
+        motor_ids: &[u8],
+    ) -> Result<ConfigReplies<SerialPort::Error>, TransferError<SerialPort::Error>> {
+        let mut results = alloc::vec::Vec::with_capacity(motor_ids.len());
+        self.bulk_read_config(motor_ids, |response| results.push(response))
+            .await?;
+        Ok(results)
+    }
+
+    /// Read every [`ConfigRegister`] of a motor, with as few `ReadCfg` transfers as the read buffer allows.
+    async fn read_config_snapshot(
+        &mut self,
+        motor_id: u8,
+    ) -> Result<Response<MotorConfig>, TransferError<SerialPort::Error>> {
+        let chunk_len = (self.max_reply_parameters() / REGISTER_BYTES).clamp(1, CONFIG_COUNT);
+        let mut values = [[0; REGISTER_BYTES]; CONFIG_COUNT];
+        let mut warning = ErrorFlags::empty();
+        for (chunk, values) in values.chunks_mut(chunk_len).enumerate() {
+            let count = values.len();
+            let response = self
+                .transfer_single(
+                    motor_id,
+                    ConfigRegister::READ_INST,
+                    count,
+                    (count * REGISTER_BYTES + 1) as u8,
+                    encode_config_addresses(chunk * chunk_len),
+                )
+                .await?;
+            InvalidParameterCount::check(response.data.len(), count * REGISTER_BYTES).map_err(InvalidMessage::from)?;
+            for (value, bytes) in values.iter_mut().zip(response.data.chunks_exact(REGISTER_BYTES)) {
+                value.copy_from_slice(bytes);
+            }
+            warning |= response.warning;
+        }
+        Ok(Response {
+            motor_id,
+            warning,
+            data: MotorConfig::from_register_bytes(&values)?,
+        })
+    }
+
+    /// Apply a config to a motor, and return the number of registers that were written.
+    ///
+    /// This checks that torque is disabled, as the motor ignores config writes while torque is enabled,
+    /// and that no other motor answers at the ID in the config if it differs from `motor_id`.
+    /// It then writes only the registers that differ from the current config.
+    /// If `save` is true and any register was written, the config is saved to flash.
+    /// Finally the config is read back to verify it was applied.
+    ///
+    /// The baud rate and ID are written last. As with [`Self::change_baud_rate`] and [`Self::change_id`],
+    /// the serial port is switched to the new baud rate and the motor is addressed at its new ID from then on.
+    /// If writing the ID, saving or verifying fails after that, the serial port is switched back to the old
+    /// baud rate, which the unsaved motor returns to when it reboots.
+    pub async fn apply_config(
+        &mut self,
+        motor_id: u8,
+        config: &MotorConfig,
+        save: bool,
+    ) -> Result<usize, ApplyConfigError<SerialPort::Error>> {
+        let new_id = match u8::try_from(config.id) {
+            Ok(id) if id != BROADCAST_ID => id,
+            _ => return Err(ApplyConfigError::InvalidId { id: config.id }),
+        };
+        let torque = self
+            .read::<status::TorqueEnable>(motor_id)
+            .await
+            .map_err(ApplyConfigError::Read)?;
+        if torque.data != TorqueState::Disabled {
+            return Err(ApplyConfigError::TorqueEnabled { motor_id });
+        }
+        let current = self.read_all_config(motor_id).await.map_err(ApplyConfigError::Read)?;
+        if new_id != motor_id {
+            let timeout = self.response_timeout_padding;
+            if self
+                .scan_one(new_id, timeout)
+                .await
+                .map_err(ApplyConfigError::CheckFree)?
+                .is_some()
+            {
+                return Err(ApplyConfigError::IdInUse { motor_id: new_id });
+            }
+        }
+
+        let old_baud_rate = self.baud_rate;
+        let result = self
+            .write_config_changes(motor_id, new_id, config, &current, save)
+            .await;
+        let unsaved = matches!(
+            result,
+            Err(ApplyConfigError::Write { .. } | ApplyConfigError::Save(_) | ApplyConfigError::Verify(_))
+        );
+        if unsaved && self.baud_rate != old_baud_rate && self.set_baud_rate(old_baud_rate).is_err() {
+            warn!(
+                "failed to restore the baud rate of the serial port to {}",
+                old_baud_rate
+            );
+        }
+        result
+    }
+
+    /// Write the registers of `config` that differ from `current`, then save and verify them, see
+    /// [`Self::apply_config`].
+    async fn write_config_changes(
+        &mut self,
+        motor_id: u8,
+        new_id: u8,
+        config: &MotorConfig,
+        current: &MotorConfig,
+        save: bool,
+    ) -> Result<usize, ApplyConfigError<SerialPort::Error>> {
+        let registers = ConfigRegister::iter()
+            .filter(|register| !matches!(register, ConfigRegister::BaudRate | ConfigRegister::Id))
+            .chain([ConfigRegister::BaudRate, ConfigRegister::Id]);
+        let mut motor_id = motor_id;
+        let mut written = 0;
+        for register in registers {
+            let value = config.register_bytes(register);
+            if value == current.register_bytes(register) {
+                continue;
+            }
+            debug!(
+                "motor {:#04X}: writing {} = {}",
+                motor_id,
+                register,
+                config.get(register)
+            );
+            self.write_config(motor_id, register, &value)
+                .await
+                .map_err(|source| ApplyConfigError::Write { register, source })?;
+            written += 1;
+            match register {
+                ConfigRegister::BaudRate => self
+                    .set_baud_rate(config.baud_rate.bits_per_second())
+                    .map_err(ApplyConfigError::SetBaudRate)?,
+                ConfigRegister::Id => motor_id = new_id,
+                _ => (),
+            }
+        }
+
+        if save && written > 0 {
+            self.save_config(motor_id).await.map_err(ApplyConfigError::Save)?;
+        }
+        let applied = self.read_all_config(motor_id).await.map_err(ApplyConfigError::Verify)?;
+        if let Some(register) =
+            ConfigRegister::iter().find(|&register| applied.register_bytes(register) != config.register_bytes(register))
+        {
+            return Err(ApplyConfigError::Mismatch { register });
+        }
+        Ok(written)
+    }
+}
+
+/// Encode the addresses of the config registers from index `first` on, as many as the parameters hold.
+fn encode_config_addresses(first: usize) -> impl FnOnce(&mut [u8]) -> Result<(), BufferTooSmallError> {
+    move |buffer| {
+        for (address, register) in buffer.iter_mut().zip(ConfigRegister::iter().skip(first)) {
+            *address = register as u8;
+        }
+        Ok(())
+    }
+}
+
+/// Decode a reply holding every config register.
+fn decode_config<E>(response: &Response<&[u8]>) -> Result<Response<MotorConfig>, ReadError<E>> {
+    InvalidParameterCount::check(response.data.len(), CONFIG_COUNT * REGISTER_BYTES).map_err(InvalidMessage::from)?;
+    let mut values = [[0; REGISTER_BYTES]; CONFIG_COUNT];
+    for (value, bytes) in values.iter_mut().zip(response.data.chunks_exact(REGISTER_BYTES)) {
+        value.copy_from_slice(bytes);
+    }
+    Ok(Response {
+        motor_id: response.motor_id,
+        warning: response.warning,
+        data: MotorConfig::from_register_bytes(&values)?,
+    })
+}
@@ -10,8 +10,7 @@ where
 {
     /// Ping a speific motor by ID
     pub async fn ping(&mut self, motor_id: u8) -> Result<Response<&[u8]>, TransferError<SerialPort::Error>> {
-        self.write_packet(motor_id, Instruction::Ping as u8, 0, |_| Ok(())).await?;
-        let r = self.read_response(4).await?;
-        Ok(r)
+        self.transfer_single(motor_id, Instruction::Ping as u8, 0, 4, |_| Ok(()))
+            .await
     }
 }
@@ -1,7 +1,7 @@
 use super::super::Bus;
 use crate::error::TransferError;
 use crate::protocol::Response;
-use crate::{ConfigRegister, StatusRegister};
+use crate::{AnyRegister, ConfigRegister, RegisterValue, StatusRegister};
 
 #[super::super::bisync]
 impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
@@ -55,4 +55,40 @@ where
         };
         Ok(r)
     }
+
+    /// Read a register chosen at runtime from a specific motor.
+    ///
+    /// The value is decoded using the type given by the register's [`crate::RegisterInfo`].
+    pub async fn read_value(
+        &mut self,
+        motor_id: u8,
+        register: AnyRegister,
+    ) -> Result<Response<RegisterValue>, TransferError<SerialPort::Error>> {
+        let info = register.info();
+        let r = self
+            .read_raw(motor_id, register.read_instruction(), info.address)
+            .await?;
+        Ok(Response {
+            motor_id: r.motor_id,
+            warning: r.warning,
+            data: RegisterValue::decode(info.value_type, r.data)?,
+        })
+    }
+
+    /// Read a register holding a physical quantity from a specific motor.
+    ///
+    /// Like [`Self::read`], but the value is converted from the unit used by the motor to a [`uom`] quantity.
+    /// Requires the `uom` feature.
+    #[cfg(feature = "uom")]
+    pub async fn read_quantity<R: crate::registers::QuantityRegister>(
+        &mut self,
+        motor_id: u8,
+    ) -> Result<Response<R::Quantity>, TransferError<SerialPort::Error>> {
+        let r = self.read::<R>(motor_id).await?;
+        Ok(Response {
+            motor_id: r.motor_id,
+            warning: r.warning,
+            data: R::to_quantity(r.data),
+        })
+    }
 }
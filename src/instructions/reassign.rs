@@ -0,0 +1,116 @@
+//! Changing the ID or baud rate of a motor.
+//!
+//! A motor answers at its new ID or baud rate as soon as the write is acknowledged, so the config must be
+//! saved at the new address for the change to persist across reboots.
+//!
+//! The motor also acknowledges config writes it ignores, such as while torque is enabled, so the register is
+//! read back at the new ID or baud rate to check that the write took effect.
+
+use super::super::Bus;
+use crate::Baud;
+use crate::error::{ChangeBaudRateError, ChangeIdError};
+use crate::protocol::BROADCAST_ID;
+use crate::registers::{Register, config};
+use log::{debug, warn};
+
+#[super::super::bisync]
+impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
+where
+    SerialPort: super::super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Change the ID of a motor from `old_id` to `new_id`, and save it to flash.
+    ///
+    /// This checks that no motor answers at `new_id`, writes the new ID, saves the config
+    /// and pings the motor at its new ID to confirm the change.
+    /// Neither ID may be the broadcast ID.
+    ///
+    /// Torque must be disabled, as the motor ignores config writes while torque is enabled.
+    /// A write that does not take effect is reported as [`ChangeIdError::Rejected`].
+    pub async fn change_id(&mut self, old_id: u8, new_id: u8) -> Result<(), ChangeIdError<SerialPort::Error>> {
+        // A write to the broadcast ID would move every motor on the bus.
+        for motor_id in [old_id, new_id] {
+            if motor_id == BROADCAST_ID {
+                return Err(ChangeIdError::InvalidId { motor_id });
+            }
+        }
+        if new_id == old_id {
+            return Ok(());
+        }
+        let timeout = self.response_timeout_padding;
+        if self
+            .scan_one(new_id, timeout)
+            .await
+            .map_err(ChangeIdError::CheckFree)?
+            .is_some()
+        {
+            return Err(ChangeIdError::IdInUse { motor_id: new_id });
+        }
+
+        debug!("changing motor ID {:#04X} to {:#04X}", old_id, new_id);
+        self.write::<config::Id>(old_id, new_id.into())
+            .await
+            .map_err(ChangeIdError::Write)?;
+        if !self.config_reads_back::<config::Id>(new_id, new_id.into()).await {
+            return Err(ChangeIdError::Rejected { motor_id: old_id });
+        }
+        self.save_config(new_id).await.map_err(ChangeIdError::Save)?;
+        self.ping(new_id).await.map_err(ChangeIdError::Verify)?;
+        Ok(())
+    }
+
+    /// Change the baud rate of a motor, and save it to flash.
+    ///
+    /// This writes the new baud rate and, once the motor has accepted it, switches the serial port to it,
+    /// saves the config and pings the motor at the new baud rate to confirm the change.
+    /// If saving or confirming the change fails, the serial port is switched back to the old baud rate.
+    /// An unsaved change is lost when the motor reboots, after which it answers at the old baud rate again.
+    /// `motor_id` may not be the broadcast ID.
+    ///
+    /// Torque must be disabled, as the motor ignores config writes while torque is enabled.
+    /// A write that does not take effect is reported as [`ChangeBaudRateError::Rejected`].
+    pub async fn change_baud_rate(
+        &mut self,
+        motor_id: u8,
+        baud: Baud,
+    ) -> Result<(), ChangeBaudRateError<SerialPort::Error>> {
+        if motor_id == BROADCAST_ID {
+            return Err(ChangeBaudRateError::InvalidId { motor_id });
+        }
+        debug!("changing the baud rate of motor {:#04X} to {:?}", motor_id, baud);
+        self.write::<config::BaudRate>(motor_id, baud)
+            .await
+            .map_err(ChangeBaudRateError::Write)?;
+        let old_baud_rate = self.baud_rate;
+        self.set_baud_rate(baud.bits_per_second())
+            .map_err(ChangeBaudRateError::SetBaudRate)?;
+        let result = if !self.config_reads_back::<config::BaudRate>(motor_id, baud).await {
+            Err(ChangeBaudRateError::Rejected { motor_id })
+        } else {
+            match self.save_config(motor_id).await {
+                Ok(_) => self.ping(motor_id).await.map(drop).map_err(ChangeBaudRateError::Verify),
+                Err(e) => Err(ChangeBaudRateError::Save(e)),
+            }
+        };
+        if result.is_err() && self.set_baud_rate(old_baud_rate).is_err() {
+            warn!(
+                "failed to restore the baud rate of the serial port to {}",
+                old_baud_rate
+            );
+        }
+        result
+    }
+
+    /// Check that a config register of the motor at `motor_id` holds `expected`, after writing it.
+    ///
+    /// Any failure to read the register back counts as the write not having taken effect.
+    async fn config_reads_back<R: Register>(&mut self, motor_id: u8, expected: R::Inner) -> bool
+    where
+        R::Inner: PartialEq,
+    {
+        match self.read::<R>(motor_id).await {
+            Ok(response) => response.data == expected,
+            Err(_) => false,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use super::super::Bus;
-use crate::error::WriteError;
-use crate::protocol::Instruction;
+use crate::error::TransferError;
+use crate::protocol::{Instruction, Response};
 
 #[super::super::bisync]
 impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
@@ -11,7 +11,10 @@ where
     /// Saves the config registers of a specific motor.
     ///
     /// The saved config registers persist on reboot.
-    pub async fn save_config(&mut self, motor_id: u8) -> Result<(), WriteError<SerialPort::Error>> {
-        self.write_packet(motor_id, Instruction::SaveCfg as u8, 0, |_| Ok(())).await
+    /// Unless disabled with [`Self::set_acknowledge_writes`], this waits for the motor to acknowledge the save,
+    /// and checks the motor ID and error flags of its status reply.
+    pub async fn save_config(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+        self.send_acknowledged(motor_id, Instruction::SaveCfg as u8, 0, |_| Ok(()))
+            .await
     }
 }
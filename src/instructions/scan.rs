@@ -0,0 +1,151 @@
+//! Discovery of the motors on a bus.
+//!
+//! A scan pings every ID in a range with a short timeout, instead of the full response timeout that
+//! [`Bus::ping`] waits for, so IDs without a motor are skipped quickly.
+
+use super::super::Bus;
+use super::write::ACK_PACKET_SIZE;
+use crate::Baud;
+use crate::bus::message_transfer_time;
+use crate::error::{ReadError, ScanError, TransferError};
+use crate::protocol::{BROADCAST_ID, FoundMotor, Instruction};
+use core::time::Duration;
+use log::{debug, warn};
+
+#[super::super::bisync]
+impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
+where
+    SerialPort: super::super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Ping every ID in `motor_ids` at the current baud rate, calling `on_found` for each motor that replies.
+    ///
+    /// For each ID, the bus waits for the time a reply takes on the wire plus `timeout`,
+    /// so a short `timeout` such as 1 ms keeps the scan fast. The broadcast ID is skipped.
+    ///
+    /// Replies are accepted regardless of the [`crate::ErrorPolicy`] and pings are never retried.
+    /// Invalid replies, for example from two motors sharing an ID or noise at the wrong baud rate, are logged
+    /// and skipped.
+    pub async fn scan<Ids, F>(
+        &mut self,
+        motor_ids: Ids,
+        timeout: Duration,
+        mut on_found: F,
+    ) -> Result<(), TransferError<SerialPort::Error>>
+    where
+        Ids: IntoIterator<Item = u8>,
+        F: FnMut(FoundMotor),
+    {
+        for motor_id in motor_ids {
+            if motor_id == BROADCAST_ID {
+                continue;
+            }
+            if let Some(found) = self.scan_one(motor_id, timeout).await? {
+                on_found(found);
+            }
+        }
+        Ok(())
+    }
+
+    /// Scan `motor_ids` at every baud rate in [`Baud::ALL`], calling `on_found` for each motor that replies.
+    ///
+    /// See [`Self::scan`]. The baud rate of the serial port is restored afterwards.
+    pub async fn scan_baud_rates<Ids, F>(
+        &mut self,
+        motor_ids: Ids,
+        timeout: Duration,
+        mut on_found: F,
+    ) -> Result<(), ScanError<SerialPort::Error>>
+    where
+        Ids: IntoIterator<Item = u8> + Clone,
+        F: FnMut(FoundMotor),
+    {
+        let original_baud_rate = self.baud_rate;
+        let mut result = Ok(());
+        for baud in Baud::ALL {
+            debug!("scanning at {} baud", baud.bits_per_second());
+            if let Err(e) = self.set_baud_rate(baud.bits_per_second()) {
+                result = Err(ScanError::SetBaudRate(e));
+                break;
+            }
+            if let Err(e) = self.scan(motor_ids.clone(), timeout, &mut on_found).await {
+                result = Err(e.into());
+                break;
+            }
+        }
+        self.set_baud_rate(original_baud_rate).map_err(ScanError::SetBaudRate)?;
+        result
+    }
+
+    /// Ping every ID in `motor_ids` at the current baud rate, returning the motors that reply.
+    ///
+    /// See [`Self::scan`].
+    #[cfg(feature = "alloc")]
+    pub async fn scan_alloc<Ids>(
+        &mut self,
+        motor_ids: Ids,
+        timeout: Duration,
+    ) -> Result<alloc::vec::Vec<FoundMotor>, TransferError<SerialPort::Error>>
+    where
+        Ids: IntoIterator<Item = u8>,
+    {
+        let mut found = alloc::vec::Vec::new();
+        self.scan(motor_ids, timeout, |motor| found.push(motor)).await?;
+        Ok(found)
+    }
+
+    /// Scan `motor_ids` at every baud rate in [`Baud::ALL`], returning the motors that reply.
+    ///
+    /// See [`Self::scan_baud_rates`].
+    #[cfg(feature = "alloc")]
+    pub async fn scan_baud_rates_alloc<Ids>(
+        &mut self,
+        motor_ids: Ids,
+        timeout: Duration,
+    ) -> Result<alloc::vec::Vec<FoundMotor>, ScanError<SerialPort::Error>>
+    where
+        Ids: IntoIterator<Item = u8> + Clone,
+    {
+        let mut found = alloc::vec::Vec::new();
+        self.scan_baud_rates(motor_ids, timeout, |motor| found.push(motor))
+            .await?;
+        Ok(found)
+    }
+
+    /// Ping a single ID with a short timeout.
+    pub(crate) async fn scan_one(
+        &mut self,
+        motor_id: u8,
+        timeout: Duration,
+    ) -> Result<Option<FoundMotor>, TransferError<SerialPort::Error>> {
+        let baud_rate = self.baud_rate;
+        self.write_packet(motor_id, Instruction::Ping as u8, 0, |_| Ok(()))
+            .await?;
+        let timeout = message_transfer_time(ACK_PACKET_SIZE.into(), baud_rate) + timeout;
+        match self.read_response_unchecked(timeout).await {
+            Ok(response) if response.motor_id == motor_id => Ok(Some(FoundMotor {
+                motor_id,
+                warning: response.warning,
+                baud_rate,
+            })),
+            Ok(response) => {
+                warn!(
+                    "scan: motor {:#04X} replied to a ping for motor {:#04X}",
+                    response.motor_id, motor_id
+                );
+                Ok(None)
+            },
+            Err(ReadError::Io(e)) if SerialPort::is_timeout_error(&e) => Ok(None),
+            // Line noise, as expected when probing the wrong baud rate.
+            Err(ReadError::InvalidMessage(e)) => {
+                warn!("scan: invalid reply to a ping for motor {:#04X}: {}", motor_id, e);
+                Ok(None)
+            },
+            Err(ReadError::BufferFull(e)) => {
+                warn!("scan: invalid reply to a ping for motor {:#04X}: {}", motor_id, e);
+                Ok(None)
+            },
+            Err(e) => Err(e.into()),
+        }
+    }
+}
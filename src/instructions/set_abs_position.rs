@@ -1,6 +1,6 @@
 use super::super::Bus;
-use crate::error::WriteError;
-use crate::protocol::Instruction;
+use crate::error::TransferError;
+use crate::protocol::{Instruction, Response};
 
 #[super::super::bisync]
 impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
@@ -15,13 +15,15 @@ where
     ///
     /// Compatible with Panda, Kodiak and Mountain motors.
     ///
+    /// Unless disabled with [`Self::set_acknowledge_writes`], this waits for the motor to acknowledge the instruction,
+    /// and checks the motor ID and error flags of its status reply.
     pub async fn set_absolute_position(
         &mut self,
         motor_id: u8,
         position: f32,
         tolerance: f32,
-    ) -> Result<(), WriteError<SerialPort::Error>> {
-        self.write_packet(motor_id, Instruction::SetAbsPos as u8, 8, |buffer| {
+    ) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+        self.send_acknowledged(motor_id, Instruction::SetAbsPos as u8, 8, |buffer| {
             buffer[..4].copy_from_slice(&position.to_le_bytes());
             buffer[4..].copy_from_slice(&tolerance.to_le_bytes());
             Ok(())
@@ -0,0 +1,73 @@
+use super::super::Bus;
+use crate::error::{ReadError, TransferError};
+use crate::protocol::Response;
+use crate::registers::status;
+use crate::{BulkWriteData, ERROR_FLAGS, ErrorFlags, StatusRegister, TorqueState};
+
+#[super::super::bisync]
+impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
+where
+    SerialPort: super::super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Enable torque on a specific motor.
+    pub async fn enable_torque(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+        self.write::<status::TorqueEnable>(motor_id, TorqueState::Enabled).await
+    }
+
+    /// Disable torque on a specific motor.
+    pub async fn disable_torque(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+        self.write::<status::TorqueEnable>(motor_id, TorqueState::Disabled)
+            .await
+    }
+
+    /// Trigger an emergency stop on a specific motor.
+    ///
+    /// The motor disables torque and raises [`ErrorFlags::WATCHDOG_ESTOP`] in its acknowledgement.
+    /// That flag is expected, so it is returned in [`Response::warning`] rather than as an error,
+    /// even with [`crate::ErrorPolicy::Strict`]. Any other error flag is still handled by the error policy.
+    pub async fn estop(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+        match self.write::<status::TorqueEnable>(motor_id, TorqueState::Estop).await {
+            Err(TransferError::ReadError(ReadError::MotorError(e)))
+                if e.motor_id == motor_id
+                    && !e.flags.difference(ErrorFlags::WATCHDOG_ESTOP).intersects(ERROR_FLAGS) =>
+            {
+                Ok(Response {
+                    motor_id,
+                    warning: e.flags,
+                    data: (),
+                })
+            },
+            result => result,
+        }
+    }
+
+    /// Enable torque on several motors in a single [`crate::Instruction::BulkComm`] packet.
+    pub async fn bulk_enable_torque(&mut self, motor_ids: &[u8]) -> Result<(), TransferError<SerialPort::Error>> {
+        self.bulk_set_torque_state(motor_ids, TorqueState::Enabled).await
+    }
+
+    /// Disable torque on several motors in a single [`crate::Instruction::BulkComm`] packet.
+    pub async fn bulk_disable_torque(&mut self, motor_ids: &[u8]) -> Result<(), TransferError<SerialPort::Error>> {
+        self.bulk_set_torque_state(motor_ids, TorqueState::Disabled).await
+    }
+
+    /// Trigger an emergency stop on several motors in a single [`crate::Instruction::BulkComm`] packet.
+    ///
+    /// Write-only bulk packets are not acknowledged, so the raised [`ErrorFlags::WATCHDOG_ESTOP`] is never reported
+    /// as an error here.
+    pub async fn bulk_estop(&mut self, motor_ids: &[u8]) -> Result<(), TransferError<SerialPort::Error>> {
+        self.bulk_set_torque_state(motor_ids, TorqueState::Estop).await
+    }
+
+    async fn bulk_set_torque_state(
+        &mut self,
+        motor_ids: &[u8],
+        state: TorqueState,
+    ) -> Result<(), TransferError<SerialPort::Error>> {
+        let devices = motor_ids
+            .iter()
+            .map(|&motor_id| BulkWriteData::from_u32(motor_id, state.into()));
+        self.bulk_write(devices, &[StatusRegister::TorqueEnable]).await
+    }
+}
@@ -1,7 +1,11 @@
 use super::super::Bus;
-use crate::error::WriteError;
+use crate::error::{BufferTooSmallError, InvalidValueError, TransferError, WriteError};
+use crate::protocol::{BROADCAST_ID, Response};
 use crate::registers::WritableRegister;
-use crate::{ConfigRegister, Instruction, StatusRegister};
+use crate::{Access, AnyRegister, ConfigRegister, ErrorFlags, Instruction, RegisterValue, StatusRegister};
+
+/// Size of the empty status packet a motor sends to acknowledge a write.
+pub(crate) const ACK_PACKET_SIZE: u8 = 6;
 
 #[super::super::bisync]
 impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
@@ -9,49 +13,145 @@ where
     SerialPort: super::super::SerialPort,
     Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
+    /// Send an instruction, and wait for the motor to acknowledge it if [`Self::acknowledge_writes`] is set.
+    ///
+    /// Instructions to the broadcast ID are never acknowledged, so they are only sent.
+    pub(crate) async fn send_acknowledged<F>(
+        &mut self,
+        motor_id: u8,
+        instruction_id: u8,
+        parameter_count: usize,
+        encode_parameters: F,
+    ) -> Result<Response<()>, TransferError<SerialPort::Error>>
+    where
+        F: FnOnce(&mut [u8]) -> Result<(), BufferTooSmallError>,
+    {
+        if motor_id == BROADCAST_ID || !self.acknowledge_writes {
+            self.write_packet(motor_id, instruction_id, parameter_count, encode_parameters)
+                .await?;
+            return Ok(Response {
+                motor_id,
+                warning: ErrorFlags::empty(),
+                data: (),
+            });
+        }
+        let response = self
+            .transfer_single(
+                motor_id,
+                instruction_id,
+                parameter_count,
+                ACK_PACKET_SIZE,
+                encode_parameters,
+            )
+            .await?;
+        Ok(Response {
+            motor_id: response.motor_id,
+            warning: response.warning,
+            data: (),
+        })
+    }
+
+    /// Write a register, and wait for the motor to acknowledge it if [`Self::acknowledge_writes`] is set.
     pub(crate) async fn write_raw(
         &mut self,
         motor_id: u8,
         instruction_id: u8,
         register: u8,
         data: &[u8],
-    ) -> Result<(), WriteError<SerialPort::Error>> {
-        self.write_packet(motor_id, instruction_id, data.len() + 1, |buffer| {
+    ) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+        self.send_acknowledged(motor_id, instruction_id, data.len() + 1, |buffer| {
             buffer[0] = register;
             buffer[1..].copy_from_slice(data);
             Ok(())
-        }).await
+        })
+        .await
     }
     /// Write a [`ConfigRegister`] to a specific motor
     ///
     /// The data parameter is an encoded byte slice. Encoding is either a f32 or u32 depending on the register.
+    /// Unless disabled with [`Self::set_acknowledge_writes`], this waits for the motor to acknowledge the write,
+    /// and checks the motor ID and error flags of its status reply.
     pub async fn write_config(
         &mut self,
         motor_id: u8,
         config_register: ConfigRegister,
         data: &[u8],
-    ) -> Result<(), WriteError<SerialPort::Error>> {
+    ) -> Result<Response<()>, TransferError<SerialPort::Error>> {
         self.write_raw(motor_id, Instruction::WriteCfg as u8, config_register as u8, data).await   }
 
     /// Write a [`StatusRegister`] to a specific motor
     ///
     /// The data parameter is an encoded byte slice. Encoding is either a f32 or u32 depending on the register.
+    /// Unless disabled with [`Self::set_acknowledge_writes`], this waits for the motor to acknowledge the write,
+    /// and checks the motor ID and error flags of its status reply.
     pub async fn write_status(
         &mut self,
         motor_id: u8,
         status_register: StatusRegister,
         data: &[u8],
-    ) -> Result<(), WriteError<SerialPort::Error>> {
+    ) -> Result<Response<()>, TransferError<SerialPort::Error>> {
         self.write_raw(motor_id, Instruction::WriteStat as u8, status_register as u8, data).await   }
 
     /// Write a register to a specific motor.
     ///
     /// The register is specificed as a generic parameter, ie `Bus::write<config::TorqueEnable>`,
     /// and are avaliable in the [`crate::protocol::registers::config`] and [`crate::protocol::registers::status`] modules.
+    /// Unless disabled with [`Self::set_acknowledge_writes`], this waits for the motor to acknowledge the write,
+    /// and checks the motor ID and error flags of its status reply.
     pub async fn write<R: WritableRegister>(
         &mut self,
         motor_id: u8,
         data: R::Inner,
-    ) -> Result<(), WriteError<SerialPort::Error>> {
+    ) -> Result<Response<()>, TransferError<SerialPort::Error>> {
         self.write_raw(motor_id, R::WRITE_INST, R::ADDRESS, &R::encode_bytes(data)).await   }
+
+    /// Write a register chosen at runtime to a specific motor.
+    ///
+    /// The value must have the type given by the register's [`crate::RegisterInfo`], and be valid for the type stored
+    /// in the register, such as a known [`crate::OperatingMode`]. The register must be writable.
+    /// Otherwise [`InvalidValueError`] is returned and nothing is sent.
+    /// Unless disabled with [`Self::set_acknowledge_writes`], this waits for the motor to acknowledge the write,
+    /// and checks the motor ID and error flags of its status reply.
+    pub async fn write_value(
+        &mut self,
+        motor_id: u8,
+        register: AnyRegister,
+        value: RegisterValue,
+    ) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+        let info = register.info();
+        if info.access == Access::ReadOnly {
+            return Err(WriteError::from(InvalidValueError::ReadOnly { register: info.name }).into());
+        }
+        if info.value_type != value.value_type() {
+            return Err(WriteError::from(InvalidValueError::TypeMismatch {
+                register: info.name,
+                expected: info.value_type,
+                actual: value.value_type(),
+            })
+            .into());
+        }
+        let bytes = value.to_bytes();
+        if register.check_value(bytes).is_err() {
+            return Err(WriteError::from(InvalidValueError::UnknownValue {
+                register: info.name,
+                value: u32::from_le_bytes(bytes),
+            })
+            .into());
+        }
+        self.write_raw(motor_id, register.write_instruction(), info.address, &bytes)
+            .await
+    }
+
+    /// Write a register holding a physical quantity to a specific motor.
+    ///
+    /// Like [`Self::write`], but the value is converted from a [`uom`] quantity to the unit used by the motor.
+    /// Requires the `uom` feature.
+    #[cfg(feature = "uom")]
+    pub async fn write_quantity<R: WritableRegister + crate::registers::QuantityRegister>(
+        &mut self,
+        motor_id: u8,
+        data: R::Quantity,
+    ) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+        self.write::<R>(motor_id, R::from_quantity(data)).await
+    }
 }
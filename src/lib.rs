@@ -22,6 +22,11 @@ pub mod error;
 
 mod checksum;
 
+#[cfg(feature = "alloc")]
+pub mod sim;
+
+pub mod monitor;
+
 /// Asynchronous interface for bear motors
 #[path = "."]
 pub mod asynchronous {
@@ -31,11 +36,19 @@ pub mod asynchronous {
     mod instructions;
     mod serial_port;
     pub use serial_port::SerialPort;
+    #[cfg(feature = "alloc")]
+    mod bus_set;
+    #[cfg(feature = "alloc")]
+    pub use bus_set::{BusSet, Joint};
+    #[cfg(feature = "std")]
+    mod shared_bus;
     #[cfg(feature = "serial2-tokio")]
     /// Public re-export of the serial2-tokio crate.
     pub use serial2_tokio;
     #[cfg(feature = "serial2-tokio")]
     use serial2_tokio::SerialPort as Serial2Port;
+    #[cfg(feature = "std")]
+    pub use shared_bus::{Lock, Priority, SharedBus, SharedBusGuard};
 }
 
 // Synchronous interface exports
@@ -0,0 +1,262 @@
+//! Streaming telemetry from several motors.
+//!
+//! [`crate::Bus::monitor`] polls a set of [`StatusRegister`]s from a set of motors with [`crate::Bus::bulk_read`],
+//! at the rate given by a [`MonitorConfig`]. Every motor reply becomes a timestamped [`Sample`], which is handed to
+//! a [`TelemetrySink`]. A reply that is dropped or invalid still produces a sample, holding the error instead of the
+//! values, so every sink sees one sample per motor per cycle.
+//!
+//! With the `std` feature, [`CsvSink`] and [`JsonLinesSink`] write samples to any [`std::io::Write`].
+//!
+//! ```
+//! use ww_bear::monitor::{CsvSink, MonitorConfig};
+//! use ww_bear::sim::SimBus;
+//! use ww_bear::{Bus, StatusRegister};
+//!
+//! let mut bus = Bus::new(SimBus::with_motors(&[1, 2])).unwrap();
+//! let registers = [StatusRegister::PresentPos, StatusRegister::PresentVel];
+//! let config = MonitorConfig::new(&[1, 2], &registers, 100.0).unwrap().with_cycles(10);
+//! let mut sink = CsvSink::new(Vec::new());
+//! bus.monitor(&config, &mut sink).unwrap();
+//! assert_eq!(String::from_utf8(sink.into_inner()).unwrap().lines().count(), 1 + 10 * 2);
+//! ```
+
+use core::time::Duration;
+
+use crate::{ErrorFlags, RegisterValue, StatusRegister};
+
+/// What to poll with [`crate::Bus::monitor`], and how often.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MonitorConfig<'a> {
+    /// The motors to poll, in the order of the samples in each cycle.
+    pub motor_ids: &'a [u8],
+    /// The registers to read from every motor, at most 15.
+    pub registers: &'a [StatusRegister],
+    /// The target time between the start of two cycles.
+    ///
+    /// A cycle that takes longer delays the next one, but cycles are never run back to back to catch up.
+    pub period: Duration,
+    /// The number of cycles to run, or `None` to run until the sink fails.
+    pub cycles: Option<u64>,
+}
+
+impl<'a> MonitorConfig<'a> {
+    /// Poll `registers` from `motor_ids` at `rate` cycles per second, until the sink fails.
+    ///
+    /// Returns `None` if `rate` is not a positive number, or so small that its period does not fit a [`Duration`].
+    pub fn new(motor_ids: &'a [u8], registers: &'a [StatusRegister], rate: f32) -> Option<Self> {
+        if rate.is_nan() || rate <= 0.0 {
+            return None;
+        }
+        Some(Self {
+            motor_ids,
+            registers,
+            period: Duration::try_from_secs_f32(1.0 / rate).ok()?,
+            cycles: None,
+        })
+    }
+
+    /// Stop after `cycles` cycles.
+    pub fn with_cycles(self, cycles: u64) -> Self {
+        Self {
+            cycles: Some(cycles),
+            ..self
+        }
+    }
+}
+
+/// The reply of one motor in one monitor cycle.
+#[derive(Debug, Clone, Copy)]
+pub struct Sample<'a> {
+    /// The index of the cycle, starting at 0.
+    pub cycle: u64,
+    /// The time the cycle started, relative to the start of the monitor.
+    pub timestamp: Duration,
+    /// The motor the sample is from.
+    pub motor_id: u8,
+    /// The registers that were read, in the order of the values.
+    pub registers: &'a [StatusRegister],
+    /// The reply, or the reason it was dropped.
+    pub reply: Result<Reading<'a>, &'a dyn core::error::Error>,
+}
+
+/// The values read from a motor, see [`Sample::reply`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading<'a> {
+    /// The [`ErrorFlags`] the motor replied with.
+    pub warning: ErrorFlags,
+    /// One value per register, decoded with the type from the register's [`crate::RegisterInfo`].
+    pub values: &'a [RegisterValue],
+}
+
+/// Receives the [`Sample`]s of [`crate::Bus::monitor`].
+pub trait TelemetrySink {
+    /// The error returned when a sample can not be recorded. It stops the monitor.
+    type Error;
+
+    /// Record a sample.
+    fn record(&mut self, sample: &Sample<'_>) -> Result<(), Self::Error>;
+}
+
+impl<F, E> TelemetrySink for F
+where
+    F: FnMut(&Sample<'_>) -> Result<(), E>,
+{
+    type Error = E;
+
+    fn record(&mut self, sample: &Sample<'_>) -> Result<(), E> {
+        self(sample)
+    }
+}
+
+/// Writes samples as CSV, one row per sample, after a header row written with the first sample.
+///
+/// The columns are `cycle`, `timestamp` (in seconds), `motor_id`, `warning` (flag names separated by `|`),
+/// `error`, then one column per register. For a dropped reply, the register columns are empty.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct CsvSink<W> {
+    writer: W,
+    header_written: bool,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> CsvSink<W> {
+    /// Create a sink writing to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            header_written: false,
+        }
+    }
+
+    /// Get the writer back.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> TelemetrySink for CsvSink<W> {
+    type Error = std::io::Error;
+
+    fn record(&mut self, sample: &Sample<'_>) -> Result<(), Self::Error> {
+        let w = &mut self.writer;
+        if !self.header_written {
+            write!(w, "cycle,timestamp,motor_id,warning,error")?;
+            for register in sample.registers {
+                write!(w, ",{register}")?;
+            }
+            writeln!(w)?;
+            self.header_written = true;
+        }
+        write!(
+            w,
+            "{},{:.6},{},",
+            sample.cycle,
+            sample.timestamp.as_secs_f64(),
+            sample.motor_id
+        )?;
+        match sample.reply {
+            Ok(reading) => {
+                write_joined(w, reading.warning.names(), "|")?;
+                write!(w, ",")?;
+                for value in reading.values {
+                    write!(w, ",{value}")?;
+                }
+            },
+            Err(error) => {
+                let error = std::format!("{error}").replace('"', "\"\"");
+                write!(w, ",\"{error}\"")?;
+                for _ in sample.registers {
+                    write!(w, ",")?;
+                }
+            },
+        }
+        writeln!(w)
+    }
+}
+
+/// Writes samples as [JSON lines](https://jsonlines.org/), one object per sample.
+///
+/// Each object has `cycle`, `timestamp` (in seconds) and `motor_id`. A reply adds `warning`, a list of flag names,
+/// and `values`, an object from register name to value. A dropped reply adds `error` instead.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct JsonLinesSink<W> {
+    writer: W,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> JsonLinesSink<W> {
+    /// Create a sink writing to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Get the writer back.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> TelemetrySink for JsonLinesSink<W> {
+    type Error = std::io::Error;
+
+    fn record(&mut self, sample: &Sample<'_>) -> Result<(), Self::Error> {
+        let w = &mut self.writer;
+        write!(
+            w,
+            r#"{{"cycle":{},"timestamp":{:.6},"motor_id":{}"#,
+            sample.cycle,
+            sample.timestamp.as_secs_f64(),
+            sample.motor_id
+        )?;
+        match sample.reply {
+            Ok(reading) => {
+                write!(w, r#","warning":["#)?;
+                write_joined(w, reading.warning.names().map(|name| std::format!("\"{name}\"")), ",")?;
+                write!(w, r#"],"values":{{"#)?;
+                for (i, (register, value)) in sample.registers.iter().zip(reading.values).enumerate() {
+                    let separator = if i == 0 { "" } else { "," };
+                    match value {
+                        RegisterValue::F32(value) if !value.is_finite() => {
+                            write!(w, r#"{separator}"{register}":null"#)?
+                        },
+                        value => write!(w, r#"{separator}"{register}":{value}"#)?,
+                    }
+                }
+                write!(w, "}}")?;
+            },
+            Err(error) => {
+                write!(w, r#","error":""#)?;
+                for c in std::format!("{error}").chars() {
+                    match c {
+                        '"' => write!(w, "\\\"")?,
+                        '\\' => write!(w, "\\\\")?,
+                        c if c.is_control() => write!(w, "\\u{:04x}", c as u32)?,
+                        c => write!(w, "{c}")?,
+                    }
+                }
+                write!(w, "\"")?;
+            },
+        }
+        writeln!(w, "}}")
+    }
+}
+
+#[cfg(feature = "std")]
+fn write_joined<W: std::io::Write>(
+    w: &mut W,
+    items: impl Iterator<Item = impl core::fmt::Display>,
+    separator: &str,
+) -> std::io::Result<()> {
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            w.write_all(separator.as_bytes())?;
+        }
+        write!(w, "{item}")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use crate::error::InvalidRegisterValue;
+
+/// A baud rate supported by BEAR motors, stored in the [`crate::ConfigRegister::BaudRate`] register.
+///
+/// The discriminant of each variant is its encoding in the register.
+/// Use [`crate::Bus::change_baud_rate`] to switch a motor and the serial port together.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum Baud {
+    /// 8 Mbaud, the factory default.
+    B8000000 = 0,
+    /// 6 Mbaud.
+    B6000000 = 1,
+    /// 4 Mbaud.
+    B4000000 = 2,
+    /// 2 Mbaud.
+    B2000000 = 3,
+    /// 1 Mbaud.
+    B1000000 = 4,
+    /// 460800 baud.
+    B460800 = 5,
+    /// 115200 baud.
+    B115200 = 6,
+}
+
+impl Baud {
+    /// All supported baud rates, fastest first.
+    pub const ALL: [Baud; 7] = [
+        Baud::B8000000,
+        Baud::B6000000,
+        Baud::B4000000,
+        Baud::B2000000,
+        Baud::B1000000,
+        Baud::B460800,
+        Baud::B115200,
+    ];
+
+    /// The baud rate in bits per second, as used by [`crate::Bus::set_baud_rate`].
+    pub const fn bits_per_second(self) -> u32 {
+        match self {
+            Baud::B8000000 => 8_000_000,
+            Baud::B6000000 => 6_000_000,
+            Baud::B4000000 => 4_000_000,
+            Baud::B2000000 => 2_000_000,
+            Baud::B1000000 => 1_000_000,
+            Baud::B460800 => 460_800,
+            Baud::B115200 => 115_200,
+        }
+    }
+
+    /// Find the supported baud rate with the given bits per second, if there is one.
+    pub fn from_bits_per_second(bits_per_second: u32) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|baud| baud.bits_per_second() == bits_per_second)
+    }
+}
+
+impl TryFrom<u32> for Baud {
+    type Error = InvalidRegisterValue;
+
+    /// Decode the encoding used in the [`crate::ConfigRegister::BaudRate`] register.
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Self::ALL.get(value as usize).copied().ok_or(InvalidRegisterValue {
+            type_name: "Baud",
+            value,
+        })
+    }
+}
+
+impl From<Baud> for u32 {
+    /// Encode the baud rate for the [`crate::ConfigRegister::BaudRate`] register.
+    fn from(baud: Baud) -> Self {
+        baud as u32
+    }
+}
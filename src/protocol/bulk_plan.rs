@@ -0,0 +1,68 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::time::Duration;
+
+/// The largest packet the bus can send: `FF FF`, id, len, instruction, 253 parameters and checksum.
+pub(crate) const MAX_PACKET_LEN: usize = u8::MAX as usize + 4;
+
+/// The ID of the next plan, shared by every bus so plans built by different buses never share an ID.
+static NEXT_PLAN_ID: AtomicU32 = AtomicU32::new(0);
+
+/// A bulk transfer prepared once and sent many times, see [`crate::Bus::bulk_plan`].
+///
+/// The plan checks the shape of the transfer up front and holds the encoded packet: the header, the register
+/// lists and the motor IDs. Each call to [`crate::Bus::bulk_execute`] only patches the write data and the
+/// checksum before sending, and reads the replies with lengths and timeouts computed when the plan was built.
+///
+/// Any bus can send a plan, but the reply timeouts are computed from the baud rate and response timeout padding
+/// of the bus that built it. Rebuild the plan after changing either, as the reply timeouts are not recomputed.
+#[derive(Debug, Clone)]
+pub struct BulkPlan {
+    /// Identifies the plan to the bus, which tracks whether its write buffer still holds this layout.
+    pub(crate) id: u32,
+    /// The encoded packet, with the write data of the last cycle.
+    pub(crate) packet: [u8; MAX_PACKET_LEN],
+    /// The length of the encoded packet, including the checksum.
+    pub(crate) packet_len: usize,
+    /// The number of motors addressed by the packet.
+    pub(crate) motor_count: usize,
+    /// The index of the first motor ID in the packet.
+    pub(crate) first_id_index: usize,
+    /// The number of write bytes per motor.
+    pub(crate) write_len: usize,
+    /// The number of read bytes expected in each reply, zero if the motors do not reply.
+    pub(crate) read_len: usize,
+    /// The timeout for each reply.
+    pub(crate) reply_timeout: Duration,
+}
+
+impl BulkPlan {
+    /// Get a new plan ID.
+    pub(crate) fn next_id() -> u32 {
+        NEXT_PLAN_ID.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// The number of motors addressed by the plan.
+    pub fn motor_count(&self) -> usize {
+        self.motor_count
+    }
+
+    /// The motor IDs addressed by the plan, in packet order.
+    pub fn motor_ids(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..self.motor_count).map(|i| self.packet[self.first_id_index + i * (1 + self.write_len)])
+    }
+
+    /// The number of encoded write bytes expected for each motor: 4 bytes per write register.
+    pub fn row_len(&self) -> usize {
+        self.write_len
+    }
+
+    /// The number of read bytes in each reply: 4 bytes per read register.
+    pub fn reply_len(&self) -> usize {
+        self.read_len
+    }
+
+    /// The time to wait for each reply.
+    pub fn reply_timeout(&self) -> Duration {
+        self.reply_timeout
+    }
+}
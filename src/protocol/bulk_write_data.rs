@@ -7,8 +7,10 @@
 ///
 /// For the common case of a single write register, use [`BulkWriteData::from_f32`] or
 /// [`BulkWriteData::from_u32`] to build an entry without hand-encoding the little-endian bytes.
+/// To write several registers, [`crate::Bus::bulk_write_typed`] encodes a tuple of typed values per motor instead.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BulkWriteData<T> {
     /// The motor to write to.
     pub motor_id: u8,
@@ -0,0 +1,19 @@
+use crate::protocol::motor_error::ErrorFlags;
+
+/// A motor that replied during a bus scan.
+///
+/// See [`crate::Bus::scan`] and [`crate::Bus::scan_baud_rates`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FoundMotor {
+    /// The ID the motor replied to.
+    pub motor_id: u8,
+
+    /// The [`ErrorFlags`] the motor replied with.
+    ///
+    /// Scans accept every reply regardless of the [`crate::ErrorPolicy`], so this may contain [`crate::ERROR_FLAGS`].
+    pub warning: ErrorFlags,
+
+    /// The baud rate the motor replied at.
+    pub baud_rate: u32,
+}
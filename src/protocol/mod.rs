@@ -2,19 +2,54 @@ pub mod registers;
 
 use derive_more::Display;
 pub use registers::Register;
+pub use registers::config::MotorConfig;
 mod motor_error;
-pub use motor_error::ErrorFlags;
 pub use motor_error::{ERROR_FLAGS, WARNING_FLAGS};
+pub use motor_error::{ErrorFlags, ErrorPolicy};
 mod response;
+mod retry;
+pub use retry::{RetryOn, RetryPolicy};
 
 pub use response::Response;
+mod baud;
+pub use baud::Baud;
 mod bulk_write_data;
 pub use bulk_write_data::BulkWriteData;
+mod bulk_plan;
+pub use bulk_plan::BulkPlan;
+pub(crate) use bulk_plan::MAX_PACKET_LEN;
+mod found_motor;
+pub use found_motor::FoundMotor;
+mod register_info;
+pub use register_info::{Access, RegisterBank, RegisterInfo, ValueType};
+mod operating_mode;
+pub use operating_mode::OperatingMode;
+mod torque_state;
+pub use torque_state::TorqueState;
+mod register_value;
+pub use register_value::{AnyRegister, RegisterValue};
 
 pub(crate) const PACKET_ID: usize = 2;
 pub(crate) const PACKET_LEN: usize = 3;
 pub(crate) const PACKET_ERROR: usize = 4;
 
+/// The ID used to address all motors at once.
+pub(crate) const BROADCAST_ID: u8 = 0xFE;
+
+/// Byte offset of the parameter section within a written packet: `FF FF`, id, len, instruction.
+pub(crate) const PACKET_PARAMS_START: usize = 5;
+
+/// Bytes per register value on the wire (4 little-endian bytes).
+pub(crate) const REGISTER_BYTES: usize = 4;
+
+/// Maximum registers per direction in a bulk packet. The read and write counts
+/// share one byte (a 4-bit nibble each), so each direction supports at most 15.
+pub(crate) const MAX_BULK_REGISTERS: usize = 0x0F;
+
+/// Non-payload bytes in a status reply, added to the read length to estimate the
+/// reply's on-wire size for the read timeout.
+pub(crate) const REPLY_FRAMING_BYTES: usize = 3;
+
 /// The instructions supported by the BEAR protocol.
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -42,6 +77,7 @@ pub enum Instruction {
 /// Registers used to set motor configuration
 #[derive(Debug, Clone, Copy, strum::EnumIter, PartialEq, Eq, PartialOrd, Ord, Display)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 #[non_exhaustive]
 pub enum ConfigRegister {
@@ -110,6 +146,7 @@ pub enum ConfigRegister {
 }
 
 impl ConfigRegister {
+    pub(crate) const BANK: RegisterBank = RegisterBank::Config;
     pub(crate) const READ_INST: u8 = Instruction::ReadCfg as u8;
     pub(crate) const WRITE_INST: u8 = Instruction::WriteCfg as u8;
 }
@@ -117,6 +154,7 @@ impl ConfigRegister {
 /// Status Registers
 #[derive(Debug, Clone, Copy, strum::EnumIter, PartialEq, Eq, PartialOrd, Ord, Display)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 #[non_exhaustive]
 pub enum StatusRegister {
@@ -151,6 +189,7 @@ pub enum StatusRegister {
 }
 
 impl StatusRegister {
+    pub(crate) const BANK: RegisterBank = RegisterBank::Status;
     pub(crate) const READ_INST: u8 = Instruction::ReadStat as u8;
     pub(crate) const WRITE_INST: u8 = Instruction::WriteStat as u8;
 }
@@ -37,12 +37,106 @@ pub const WARNING_FLAGS: ErrorFlags = ErrorFlags::OVERHEAT.union(ErrorFlags::COM
 /// The [`ErrorFlags`] that represent critical errors for which action must be taken.
 pub const ERROR_FLAGS: ErrorFlags = WARNING_FLAGS.complement();
 
+/// How a [`crate::Bus`] treats responses whose error byte contains any [`ERROR_FLAGS`].
+///
+/// [`WARNING_FLAGS`] never cause a response to be rejected.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ErrorPolicy {
+    /// Reject the response with a [`crate::error::MotorError`].
+    #[default]
+    Strict,
+    /// Accept the response, but log a warning.
+    WarnOnly,
+    /// Accept the response without logging.
+    Ignore,
+}
+
+impl ErrorFlags {
+    /// The names of the set flags, such as `"OVERHEAT"`, in bit order.
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        FLAG_NAMES
+            .into_iter()
+            .filter(move |(_, flag)| self.contains(*flag))
+            .map(|(name, _)| name)
+    }
+}
+
 impl Display for ErrorFlags {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         core::fmt::Debug::fmt(&self, f)
     }
 }
 
+/// The name of each flag, see [`ErrorFlags::names`].
+const FLAG_NAMES: [(&str, ErrorFlags); 7] = [
+    ("COMMUNICATION", ErrorFlags::COMMUNICATION),
+    ("OVERHEAT", ErrorFlags::OVERHEAT),
+    ("ABSOLUTE_POSITION", ErrorFlags::ABSOLUTE_POSITION),
+    ("WATCHDOG_ESTOP", ErrorFlags::WATCHDOG_ESTOP),
+    ("JOINT_LIMIT", ErrorFlags::JOINT_LIMIT),
+    ("HARDWARE", ErrorFlags::HARDWARE),
+    ("INITIALIZATION", ErrorFlags::INITIALIZATION),
+];
+
+/// Serialized as the list of the names of the set flags, such as `["OVERHEAT", "JOINT_LIMIT"]`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ErrorFlags {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.names())
+    }
+}
+
+/// Deserialized from a list of flag names, see the [`serde::Serialize`] implementation.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ErrorFlags {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FlagsVisitor;
+        struct Flag(ErrorFlags);
+        struct FlagVisitor;
+
+        impl serde::de::Visitor<'_> for FlagVisitor {
+            type Value = Flag;
+
+            fn expecting(&self, f: &mut Formatter) -> core::fmt::Result {
+                f.write_str("the name of an error flag")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<Flag, E> {
+                FLAG_NAMES
+                    .iter()
+                    .find(|(flag_name, _)| *flag_name == name)
+                    .map(|(_, flag)| Flag(*flag))
+                    .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(name), &self))
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for Flag {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_str(FlagVisitor)
+            }
+        }
+
+        impl<'de> serde::de::Visitor<'de> for FlagsVisitor {
+            type Value = ErrorFlags;
+
+            fn expecting(&self, f: &mut Formatter) -> core::fmt::Result {
+                f.write_str("a list of error flag names")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<ErrorFlags, A::Error> {
+                let mut flags = ErrorFlags::empty();
+                while let Some(Flag(flag)) = seq.next_element()? {
+                    flags |= flag;
+                }
+                Ok(flags)
+            }
+        }
+
+        deserializer.deserialize_seq(FlagsVisitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,45 @@
+use crate::error::InvalidRegisterValue;
+
+/// The control mode of a motor, stored in the [`crate::ConfigRegister::Mode`] register.
+///
+/// The goal registers used by each mode are listed on the variants.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum OperatingMode {
+    /// Torque (current) mode, following `GoalIq`.
+    Torque = 0,
+    /// Velocity mode, following `GoalVel` using the velocity gains.
+    Velocity = 1,
+    /// Position mode, following `GoalPos` using the position gains.
+    Position = 2,
+    /// Force mode, an impedance controller around `GoalPos` and `GoalVel` using the force gains, plus `GoalIq`.
+    Force = 3,
+    /// Direct force PID mode, following `GoalPos` using the force gains.
+    DirectForce = 4,
+}
+
+impl TryFrom<u32> for OperatingMode {
+    type Error = InvalidRegisterValue;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Torque),
+            1 => Ok(Self::Velocity),
+            2 => Ok(Self::Position),
+            3 => Ok(Self::Force),
+            4 => Ok(Self::DirectForce),
+            _ => Err(InvalidRegisterValue {
+                type_name: "OperatingMode",
+                value,
+            }),
+        }
+    }
+}
+
+impl From<OperatingMode> for u32 {
+    fn from(mode: OperatingMode) -> Self {
+        mode as u32
+    }
+}
@@ -0,0 +1,51 @@
+/// The register table a register belongs to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RegisterBank {
+    /// A [`crate::ConfigRegister`], persisted with [`crate::Bus::save_config`].
+    Config,
+    /// A [`crate::StatusRegister`].
+    Status,
+}
+
+/// How a register value is encoded on the wire.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ValueType {
+    /// A little-endian `u32`, also used by the enum registers such as [`crate::OperatingMode`].
+    U32,
+    /// A little-endian `f32`.
+    F32,
+}
+
+/// Whether a register can be written.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Access {
+    /// The register can be read and written.
+    ReadWrite,
+    /// The register can only be read.
+    ReadOnly,
+}
+
+/// Metadata describing a register, for tools that work with registers at runtime.
+///
+/// See [`crate::registers::REGISTERS`], [`crate::ConfigRegister::info`] and [`crate::StatusRegister::info`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RegisterInfo {
+    /// The name of the register, as used by the register types in [`crate::registers`].
+    pub name: &'static str,
+    /// The address of the register within its bank.
+    pub address: u8,
+    /// The register table the register belongs to.
+    pub bank: RegisterBank,
+    /// How the value is encoded on the wire.
+    pub value_type: ValueType,
+    /// Whether the register can be written.
+    pub access: Access,
+    /// The symbol of the unit used by the motor, if the register holds a physical quantity.
+    pub unit: Option<&'static str>,
+    /// A short description of the register.
+    pub description: &'static str,
+}
@@ -0,0 +1,144 @@
+use core::fmt::{Display, Formatter};
+
+use crate::error::{InvalidMessage, InvalidParameterCount};
+use crate::protocol::{ConfigRegister, RegisterInfo, StatusRegister, ValueType};
+
+/// A register value whose type is only known at runtime, see [`crate::Bus::read_value`] and [`crate::Bus::write_value`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RegisterValue {
+    /// A `u32` value, also used by the enum registers such as [`crate::OperatingMode`].
+    U32(u32),
+    /// An `f32` value.
+    F32(f32),
+}
+
+impl RegisterValue {
+    /// Decode the bytes of a register with the given value type.
+    pub fn decode(value_type: ValueType, bytes: &[u8]) -> Result<Self, InvalidMessage> {
+        InvalidParameterCount::check(bytes.len(), 4)?;
+        Ok(Self::from_bytes(value_type, bytes.try_into().unwrap()))
+    }
+
+    pub(crate) fn from_bytes(value_type: ValueType, bytes: [u8; 4]) -> Self {
+        match value_type {
+            ValueType::U32 => Self::U32(u32::from_le_bytes(bytes)),
+            ValueType::F32 => Self::F32(f32::from_le_bytes(bytes)),
+        }
+    }
+
+    /// Encode the value as the bytes of a register.
+    pub fn to_bytes(self) -> [u8; 4] {
+        match self {
+            Self::U32(value) => value.to_le_bytes(),
+            Self::F32(value) => value.to_le_bytes(),
+        }
+    }
+
+    /// The type of the value.
+    pub fn value_type(self) -> ValueType {
+        match self {
+            Self::U32(_) => ValueType::U32,
+            Self::F32(_) => ValueType::F32,
+        }
+    }
+
+    /// Get the value if it is a `u32`.
+    pub fn as_u32(self) -> Option<u32> {
+        match self {
+            Self::U32(value) => Some(value),
+            Self::F32(_) => None,
+        }
+    }
+
+    /// Get the value if it is an `f32`.
+    pub fn as_f32(self) -> Option<f32> {
+        match self {
+            Self::U32(_) => None,
+            Self::F32(value) => Some(value),
+        }
+    }
+}
+
+impl From<u32> for RegisterValue {
+    fn from(value: u32) -> Self {
+        Self::U32(value)
+    }
+}
+
+impl From<f32> for RegisterValue {
+    fn from(value: f32) -> Self {
+        Self::F32(value)
+    }
+}
+
+impl Display for RegisterValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::U32(value) => Display::fmt(value, f),
+            Self::F32(value) => Display::fmt(value, f),
+        }
+    }
+}
+
+/// Either a [`ConfigRegister`] or a [`StatusRegister`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnyRegister {
+    /// A config register.
+    Config(ConfigRegister),
+    /// A status register.
+    Status(StatusRegister),
+}
+
+impl AnyRegister {
+    /// Get the metadata of the register.
+    pub const fn info(self) -> RegisterInfo {
+        match self {
+            Self::Config(register) => register.info(),
+            Self::Status(register) => register.info(),
+        }
+    }
+
+    /// Check that the bytes can be decoded into the type stored in the register.
+    pub(crate) fn check_value(self, bytes: [u8; 4]) -> Result<(), InvalidMessage> {
+        match self {
+            Self::Config(register) => register.check_value(bytes),
+            Self::Status(register) => register.check_value(bytes),
+        }
+    }
+
+    pub(crate) const fn read_instruction(self) -> u8 {
+        match self {
+            Self::Config(_) => ConfigRegister::READ_INST,
+            Self::Status(_) => StatusRegister::READ_INST,
+        }
+    }
+
+    pub(crate) const fn write_instruction(self) -> u8 {
+        match self {
+            Self::Config(_) => ConfigRegister::WRITE_INST,
+            Self::Status(_) => StatusRegister::WRITE_INST,
+        }
+    }
+}
+
+impl From<ConfigRegister> for AnyRegister {
+    fn from(register: ConfigRegister) -> Self {
+        Self::Config(register)
+    }
+}
+
+impl From<StatusRegister> for AnyRegister {
+    fn from(register: StatusRegister) -> Self {
+        Self::Status(register)
+    }
+}
+
+impl Display for AnyRegister {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.info().name)
+    }
+}
@@ -3,24 +3,115 @@
 //!
 //! Status registers are not persistant and writable registers can be updated when torque is enabled.
 //!
-//! This module also contains [`Register`] and [`WritableRegister`] traits used for working with registers generically.
+//! This module also contains [`Register`] and [`WritableRegister`] traits used for working with registers generically,
+//! and the [`RegisterData`] trait for the types stored in registers.
 use crate::error::{BufferTooSmallError, InvalidMessage};
+use crate::protocol::REGISTER_BYTES;
+use crate::{Access, Baud, OperatingMode, RegisterInfo, RegisterValue, StatusRegister, TorqueState, ValueType};
+
+/// A type that can be stored in a register, encoded as 4 little-endian bytes on the wire.
+pub trait RegisterData: Sized {
+    /// How the value is encoded on the wire.
+    const VALUE_TYPE: ValueType;
+
+    /// Decode the value from the bytes of a register.
+    fn from_register_bytes(bytes: [u8; 4]) -> Result<Self, InvalidMessage>;
+    /// Encode the value into the bytes of a register.
+    fn to_register_bytes(self) -> [u8; 4];
+}
+
+impl RegisterData for u32 {
+    const VALUE_TYPE: ValueType = ValueType::U32;
+
+    fn from_register_bytes(bytes: [u8; 4]) -> Result<Self, InvalidMessage> {
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn to_register_bytes(self) -> [u8; 4] {
+        self.to_le_bytes()
+    }
+}
+
+impl RegisterData for f32 {
+    const VALUE_TYPE: ValueType = ValueType::F32;
+
+    fn from_register_bytes(bytes: [u8; 4]) -> Result<Self, InvalidMessage> {
+        Ok(f32::from_le_bytes(bytes))
+    }
+
+    fn to_register_bytes(self) -> [u8; 4] {
+        self.to_le_bytes()
+    }
+}
+
+impl RegisterData for OperatingMode {
+    const VALUE_TYPE: ValueType = ValueType::U32;
+
+    fn from_register_bytes(bytes: [u8; 4]) -> Result<Self, InvalidMessage> {
+        Ok(OperatingMode::try_from(u32::from_le_bytes(bytes))?)
+    }
+
+    fn to_register_bytes(self) -> [u8; 4] {
+        u32::from(self).to_le_bytes()
+    }
+}
+
+impl RegisterData for Baud {
+    const VALUE_TYPE: ValueType = ValueType::U32;
+
+    fn from_register_bytes(bytes: [u8; 4]) -> Result<Self, InvalidMessage> {
+        Ok(Baud::try_from(u32::from_le_bytes(bytes))?)
+    }
+
+    fn to_register_bytes(self) -> [u8; 4] {
+        u32::from(self).to_le_bytes()
+    }
+}
+
+impl RegisterData for TorqueState {
+    const VALUE_TYPE: ValueType = ValueType::U32;
+
+    fn from_register_bytes(bytes: [u8; 4]) -> Result<Self, InvalidMessage> {
+        Ok(TorqueState::try_from(u32::from_le_bytes(bytes))?)
+    }
+
+    fn to_register_bytes(self) -> [u8; 4] {
+        u32::from(self).to_le_bytes()
+    }
+}
 
 /// Implemented by each register, this trait is used with [`crate::Bus::read`].
 pub trait Register {
     /// The register name, useful for debugging but currently unused.
     const NAME: &'static str;
     /// The inner type that can be read or written to the register
-    type Inner;
+    type Inner: RegisterData;
     /// The instruction used for reading this register
     const READ_INST: u8;
     /// The address that register data is read from or written to
     const ADDRESS: u8;
+    /// The metadata of the register.
+    const INFO: RegisterInfo;
 
     /// Decode the value from the given buffer.
     fn decode(buffer: &[u8]) -> Result<Self::Inner, InvalidMessage>;
 }
 
+/// Implemented by registers holding a physical quantity, this trait is used with [`crate::Bus::read_quantity`]
+/// and [`crate::Bus::write_quantity`].
+///
+/// Requires the `uom` feature.
+#[cfg(feature = "uom")]
+pub trait QuantityRegister: Register<Inner = f32> {
+    /// The physical quantity stored in the register.
+    type Quantity;
+
+    /// Convert a raw register value, in the unit used by the motor, to a quantity.
+    fn to_quantity(value: f32) -> Self::Quantity;
+    /// Convert a quantity to a raw register value, in the unit used by the motor.
+    fn from_quantity(quantity: Self::Quantity) -> f32;
+}
+
 /// Implemented by all writeable registers, this trait is used with [`crate::Bus::write`].
 pub trait WritableRegister: Register {
     /// The instruction used for writing to this register
@@ -34,14 +125,79 @@ pub trait WritableRegister: Register {
     fn encode_bytes(data: Self::Inner) -> [u8; 4];
 }
 
+/// Implemented by status registers, which can be used with the bulk instructions.
+pub trait BulkRegister: Register {
+    /// The register, as used by [`crate::Bus::bulk_read`].
+    const REGISTER: StatusRegister;
+}
+
+/// A tuple of [`BulkRegister`]s read together with [`crate::Bus::bulk_read_typed`].
+///
+/// Implemented for tuples of up to 12 registers, such as `(status::PresentPos, status::PresentVel)`.
+/// The values are decoded into a tuple of the same length, such as `(f32, f32)`.
+pub trait RegisterSet {
+    /// The decoded values, one per register.
+    type Values;
+    /// The registers, in the order of the values.
+    const REGISTERS: &'static [StatusRegister];
+
+    /// Decode the values from the concatenated bytes of the registers.
+    fn decode(data: &[u8]) -> Result<Self::Values, InvalidMessage>;
+}
+
+/// A tuple of writable [`BulkRegister`]s written together with [`crate::Bus::bulk_write_typed`].
+///
+/// Implemented for tuples of up to 12 writable registers, such as `(status::GoalPos, status::GoalVel)`.
+pub trait WritableRegisterSet: RegisterSet {
+    /// Encode the values into the concatenated bytes of the registers, using [`WritableRegister::encode`].
+    fn encode(values: Self::Values, buffer: &mut [u8]) -> Result<(), BufferTooSmallError>;
+}
+
+macro_rules! impl_register_set {
+    ($( $name:ident $index:tt ),+) => {
+        impl<$( $name: BulkRegister ),+> RegisterSet for ($( $name, )+) {
+            type Values = ($( $name::Inner, )+);
+            const REGISTERS: &'static [StatusRegister] = &[$( $name::REGISTER ),+];
+
+            fn decode(data: &[u8]) -> Result<Self::Values, InvalidMessage> {
+                crate::error::InvalidParameterCount::check(data.len(), Self::REGISTERS.len() * REGISTER_BYTES)?;
+                Ok(($( $name::decode(&data[$index * REGISTER_BYTES..][..REGISTER_BYTES])?, )+))
+            }
+        }
+
+        impl<$( $name: BulkRegister + WritableRegister ),+> WritableRegisterSet for ($( $name, )+) {
+            fn encode(values: Self::Values, buffer: &mut [u8]) -> Result<(), BufferTooSmallError> {
+                BufferTooSmallError::check(Self::REGISTERS.len() * REGISTER_BYTES, buffer.len())?;
+                $( $name::encode(values.$index, &mut buffer[$index * REGISTER_BYTES..][..REGISTER_BYTES])?; )+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_register_set!(A 0);
+impl_register_set!(A 0, B 1);
+impl_register_set!(A 0, B 1, C 2);
+impl_register_set!(A 0, B 1, C 2, D 3);
+impl_register_set!(A 0, B 1, C 2, D 3, E 4);
+impl_register_set!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_register_set!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_register_set!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_register_set!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_register_set!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_register_set!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+impl_register_set!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
 const fn to_u8(input: usize) -> u8 {
     assert!(input <= u8::MAX as usize);
     input as u8
 }
 macro_rules! register {
-    (@REGISTER $register:ident : $r_type:ty, $addr:expr, $inner:ty) => {
+    (@REGISTER $register:ident : $r_type:ty, $addr:expr, $inner:ty, $access:expr, $description:literal, $unit:expr) => {
         #[derive(Debug, Clone, PartialEq)]
         #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+        #[doc = $description]
+        ///
         #[doc = concat!("[`",stringify!($register),"`] register at address `",stringify!($addr), "`")]
         #[doc = concat!("[`",stringify!($register),"`] is of type [`", stringify!($inner), "`]")]
         pub struct $register;
@@ -51,12 +207,19 @@ macro_rules! register {
             type Inner = $inner;
             const ADDRESS: u8 = $addr as u8;
             const READ_INST: u8 = <$r_type>::READ_INST;
+            const INFO: RegisterInfo = RegisterInfo {
+                name: stringify!($register),
+                address: $addr as u8,
+                bank: <$r_type>::BANK,
+                value_type: <$inner as RegisterData>::VALUE_TYPE,
+                access: $access,
+                unit: $unit,
+                description: $description,
+            };
 
             fn decode(buffer: &[u8]) -> Result<Self::Inner, InvalidMessage> {
-                const N: usize = core::mem::size_of::<$inner>();
-                crate::error::InvalidParameterCount::check(buffer.len(), N)?;
-                let value = Self::Inner::from_le_bytes(buffer[0..N].try_into().unwrap());
-                Ok(value)
+                crate::error::InvalidParameterCount::check(buffer.len(), REGISTER_BYTES)?;
+                <$inner as RegisterData>::from_register_bytes(buffer[..REGISTER_BYTES].try_into().unwrap())
             }
         }
         impl<SerialPort, Buffer> crate::asynchronous::Bus<SerialPort, Buffer>
@@ -81,22 +244,21 @@ macro_rules! register {
         }
     };
 
-    (@WRITABLE $register:ident : $r_type:ty, $addr:expr, $inner:ty) => {
-        register!(@REGISTER $register: $r_type, $addr, $inner);
+    (@WRITABLE $register:ident : $r_type:ty, $addr:expr, $inner:ty, $description:literal, $unit:expr) => {
+        register!(@REGISTER $register: $r_type, $addr, $inner, Access::ReadWrite, $description, $unit);
         impl WritableRegister for $register {
-            const ENCODED_SIZE: u8 = to_u8(core::mem::size_of::<Self::Inner>());
+            const ENCODED_SIZE: u8 = to_u8(REGISTER_BYTES);
 
             const WRITE_INST: u8 = <$r_type>::WRITE_INST;
 
             fn encode(data: Self::Inner, buffer: &mut [u8]) -> Result<(), BufferTooSmallError> {
-                const N: usize = core::mem::size_of::<$inner>();
-                crate::error::BufferTooSmallError::check(N, buffer.len())?;
-                buffer[..N].copy_from_slice(&data.to_le_bytes());
+                crate::error::BufferTooSmallError::check(REGISTER_BYTES, buffer.len())?;
+                buffer[..REGISTER_BYTES].copy_from_slice(&data.to_register_bytes());
                 Ok(())
             }
 
-            fn encode_bytes(data: Self::Inner) -> [u8; core::mem::size_of::<Self::Inner>()] {
-                data.to_le_bytes()
+            fn encode_bytes(data: Self::Inner) -> [u8; REGISTER_BYTES] {
+                data.to_register_bytes()
             }
         }
         impl<SerialPort, Buffer> crate::Bus<SerialPort, Buffer>
@@ -104,7 +266,7 @@ macro_rules! register {
                     Buffer: AsMut<[u8]> + AsRef<[u8]> {
             paste::item!{
                 #[doc = "write a `" $inner "` to the `" $register "` of a specific motor."]
-                pub fn [<write_ $register:snake>](&mut self, id: u8, data: $inner) -> Result<(), WriteError<SerialPort::Error>> {
+                pub fn [<write_ $register:snake>](&mut self, id: u8, data: $inner) -> Result<Response<()>, TransferError<SerialPort::Error>> {
                     self.write::<$register>(id, data)
                 }
             }
@@ -114,25 +276,172 @@ macro_rules! register {
                     Buffer: AsMut<[u8]> + AsRef<[u8]> {
             paste::item!{
                 #[doc = "write a `" $inner "` to the `" $register "` of a specific motor."]
-                pub async fn [<write_ $register:snake>](&mut self, id: u8, data: $inner) -> Result<(), WriteError<SerialPort::Error>> {
+                pub async fn [<write_ $register:snake>](&mut self, id: u8, data: $inner) -> Result<Response<()>, TransferError<SerialPort::Error>> {
                     self.write::<$register>(id, data).await
                 }
             }
         }
     };
-    (ConfigRegister::$register:ident, $inner:ty, RW) => {
-        register!(@WRITABLE  $register: ConfigRegister, ConfigRegister::$register, $inner);
+    (@QUANTITY $register:ident, $quantity:ident, $($unit:ident)::+) => {
+        #[cfg(feature = "uom")]
+        impl QuantityRegister for $register {
+            type Quantity = uom::si::f32::$quantity;
+
+            fn to_quantity(value: f32) -> Self::Quantity {
+                Self::Quantity::new::<uom::si::$($unit)::+>(value)
+            }
+
+            fn from_quantity(quantity: Self::Quantity) -> f32 {
+                quantity.get::<uom::si::$($unit)::+>()
+            }
+        }
+        #[cfg(feature = "uom")]
+        impl<SerialPort, Buffer> crate::asynchronous::Bus<SerialPort, Buffer>
+            where SerialPort: crate::asynchronous::SerialPort,
+                    Buffer: AsMut<[u8]> + AsRef<[u8]> {
+            paste::item!{
+                #[doc = "read the `" $register "` from a specific motor as an [`uom::si::f32::" $quantity "`]."]
+                pub async fn [<read_ $register:snake _quantity>](&mut self, id: u8) -> Result<Response<uom::si::f32::$quantity>, TransferError<SerialPort::Error>> {
+                    self.read_quantity::<$register>(id).await
+                }
+            }
+        }
+        #[cfg(feature = "uom")]
+        impl<SerialPort, Buffer> crate::Bus<SerialPort, Buffer>
+            where SerialPort: crate::SerialPort,
+                    Buffer: AsMut<[u8]> + AsRef<[u8]> {
+            paste::item!{
+                #[doc = "read the `" $register "` from a specific motor as an [`uom::si::f32::" $quantity "`]."]
+                pub fn [<read_ $register:snake _quantity>](&mut self, id: u8) -> Result<Response<uom::si::f32::$quantity>, TransferError<SerialPort::Error>> {
+                    self.read_quantity::<$register>(id)
+                }
+            }
+        }
+    };
+
+    (@WRITABLE_QUANTITY $register:ident, $quantity:ident) => {
+        #[cfg(feature = "uom")]
+        impl<SerialPort, Buffer> crate::Bus<SerialPort, Buffer>
+            where SerialPort: crate::SerialPort,
+                    Buffer: AsMut<[u8]> + AsRef<[u8]> {
+            paste::item!{
+                #[doc = "write an [`uom::si::f32::" $quantity "`] to the `" $register "` of a specific motor."]
+                pub fn [<write_ $register:snake _quantity>](&mut self, id: u8, data: uom::si::f32::$quantity) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+                    self.write_quantity::<$register>(id, data)
+                }
+            }
+        }
+        #[cfg(feature = "uom")]
+        impl<SerialPort, Buffer> crate::asynchronous::Bus<SerialPort, Buffer>
+            where SerialPort: crate::asynchronous::SerialPort,
+                    Buffer: AsMut<[u8]> + AsRef<[u8]> {
+            paste::item!{
+                #[doc = "write an [`uom::si::f32::" $quantity "`] to the `" $register "` of a specific motor."]
+                pub async fn [<write_ $register:snake _quantity>](&mut self, id: u8, data: uom::si::f32::$quantity) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+                    self.write_quantity::<$register>(id, data).await
+                }
+            }
+        }
     };
-    (StatusRegister::$register:ident, $inner:ty, RW) => {
-        register!(@WRITABLE $register: StatusRegister, StatusRegister::$register, $inner);
+    (@UNIT) => { None };
+    (@UNIT $symbol:literal) => { Some($symbol) };
+    ($bank:ident::$register:ident, $inner:ty, RW, $description:literal $(, $quantity:ident, $($unit:ident)::+, $symbol:literal)?) => {
+        register!(@WRITABLE $register: $bank, $bank::$register, $inner, $description, register!(@UNIT $($symbol)?));
+        $(
+            register!(@QUANTITY $register, $quantity, $($unit)::+);
+            register!(@WRITABLE_QUANTITY $register, $quantity);
+        )?
     };
-    (ConfigRegister::$register:ident, $inner:ty, RO) => {
-        register!(@REGISTER $register: ConfigRegister, ConfigRegister::$register, $inner);
+    ($bank:ident::$register:ident, $inner:ty, RO, $description:literal $(, $quantity:ident, $($unit:ident)::+, $symbol:literal)?) => {
+        register!(@REGISTER $register: $bank, $bank::$register, $inner, Access::ReadOnly, $description, register!(@UNIT $($symbol)?));
+        $( register!(@QUANTITY $register, $quantity, $($unit)::+); )?
     };
-    (StatusRegister::$register:ident, $inner:ty, RO) => {
-        register!(@REGISTER $register: StatusRegister, StatusRegister::$register, $inner);
+}
+
+/// Declares the registers of a bank, along with the bank's `REGISTERS` table and `info()` method.
+///
+/// With `=> $snapshot`, also declares a struct holding the value of every register in the bank.
+/// The registers of the `StatusRegister` bank also implement [`BulkRegister`].
+macro_rules! register_bank {
+    (@BANK $bank:ident {
+        $( $register:ident: $inner:ty, $access:ident, $description:literal $(, $quantity:ident, $($unit:ident)::+, $symbol:literal)?; )*
+    }) => {
+        $( register!($bank::$register, $inner, $access, $description $(, $quantity, $($unit)::+, $symbol)?); )*
+
+        /// The metadata of every register in this bank, in address order.
+        pub const REGISTERS: &[RegisterInfo] = &[$( <$register as Register>::INFO ),*];
+
+        impl $bank {
+            /// Get the metadata of this register.
+            pub const fn info(self) -> RegisterInfo {
+                match self {
+                    $( $bank::$register => <$register as Register>::INFO, )*
+                }
+            }
+
+            /// Check that the bytes can be decoded into the type stored in this register.
+            pub(crate) fn check_value(self, bytes: [u8; 4]) -> Result<(), InvalidMessage> {
+                match self {
+                    $( $bank::$register => <$inner as RegisterData>::from_register_bytes(bytes).map(drop), )*
+                }
+            }
+        }
+    };
+    (StatusRegister {
+        $( $register:ident: $inner:ty, $access:ident, $description:literal $(, $quantity:ident, $($unit:ident)::+, $symbol:literal)?; )*
+    }) => {
+        register_bank!(@BANK StatusRegister {
+            $( $register: $inner, $access, $description $(, $quantity, $($unit)::+, $symbol)?; )*
+        });
+
+        $(
+            impl BulkRegister for $register {
+                const REGISTER: StatusRegister = StatusRegister::$register;
+            }
+        )*
     };
+    ($bank:ident => $snapshot:ident {
+        $( $register:ident: $inner:ty, $access:ident, $description:literal $(, $quantity:ident, $($unit:ident)::+, $symbol:literal)?; )*
+    }) => {
+        register_bank!(@BANK $bank {
+            $( $register: $inner, $access, $description $(, $quantity, $($unit)::+, $symbol)?; )*
+        });
 
+        paste::paste! {
+            #[doc = "The value of every [`" $bank "`] of a motor."]
+            #[derive(Debug, Clone, Copy, PartialEq)]
+            #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+            pub struct $snapshot {
+                $(
+                    #[doc = $description]
+                    pub [<$register:snake>]: $inner,
+                )*
+            }
+
+            impl $snapshot {
+                /// Get the value of a register.
+                pub fn get(&self, register: $bank) -> RegisterValue {
+                    RegisterValue::from_bytes(register.info().value_type, self.register_bytes(register))
+                }
+
+                /// Get the encoded value of a register.
+                pub(crate) fn register_bytes(&self, register: $bank) -> [u8; 4] {
+                    match register {
+                        $( $bank::$register => self.[<$register:snake>].to_register_bytes(), )*
+                    }
+                }
+
+                /// Decode the values of every register, given in address order.
+                pub(crate) fn from_register_bytes(values: &[[u8; 4]; REGISTERS.len()]) -> Result<Self, InvalidMessage> {
+                    let mut values = values.iter();
+                    Ok(Self {
+                        $( [<$register:snake>]: <$inner>::from_register_bytes(*values.next().unwrap())?, )*
+                    })
+                }
+            }
+        }
+    };
 }
 
 /// Structs representing each config register.
@@ -140,38 +449,40 @@ macro_rules! register {
 pub mod config {
     use super::*;
     use crate::Response;
-    use crate::error::{BufferTooSmallError, InvalidMessage, TransferError, WriteError};
+    use crate::error::{BufferTooSmallError, InvalidMessage, TransferError};
     use crate::protocol::ConfigRegister;
-    register!(ConfigRegister::Id, u32, RW);
-    register!(ConfigRegister::Mode, u32, RW);
-    register!(ConfigRegister::BaudRate, u32, RW);
-    register!(ConfigRegister::HomingOffset, f32, RW);
-    register!(ConfigRegister::PGainId, f32, RW);
-    register!(ConfigRegister::IGainId, f32, RW);
-    register!(ConfigRegister::DGainId, f32, RW);
-    register!(ConfigRegister::PGainIq, f32, RW);
-    register!(ConfigRegister::IGainIq, f32, RW);
-    register!(ConfigRegister::DGainIq, f32, RW);
-    register!(ConfigRegister::PGainVel, f32, RW);
-    register!(ConfigRegister::IGainVel, f32, RW);
-    register!(ConfigRegister::DGainVel, f32, RW);
-    register!(ConfigRegister::PGainPos, f32, RW);
-    register!(ConfigRegister::IGainPos, f32, RW);
-    register!(ConfigRegister::DGainPos, f32, RW);
-    register!(ConfigRegister::PGainForce, f32, RW);
-    register!(ConfigRegister::IGainForce, f32, RW);
-    register!(ConfigRegister::DGainForce, f32, RW);
-    register!(ConfigRegister::LimitAccMax, f32, RW);
-    register!(ConfigRegister::LimitIMax, f32, RW);
-    register!(ConfigRegister::LimitVelMax, f32, RW);
-    register!(ConfigRegister::LimitPosMin, f32, RW);
-    register!(ConfigRegister::LimitPosMax, f32, RW);
-    register!(ConfigRegister::MinVoltage, f32, RW);
-    register!(ConfigRegister::MaxVoltage, f32, RW);
-    register!(ConfigRegister::WatchdogTimeout, u32, RW);
-    register!(ConfigRegister::TempLimitLow, f32, RW);
-    register!(ConfigRegister::TempLimitHigh, f32, RW);
-    register!(ConfigRegister::ReturnTimeDelay, u32, RW);
+    register_bank!(ConfigRegister => MotorConfig {
+        Id: u32, RW, "The ID the motor answers to.";
+        Mode: OperatingMode, RW, "The control mode of the motor.";
+        BaudRate: Baud, RW, "The baud rate of the motor's serial port.";
+        HomingOffset: f32, RW, "The offset between the encoder position and the present position.", Angle, angle::radian, "rad";
+        PGainId: f32, RW, "Proportional gain of the d-axis current loop.";
+        IGainId: f32, RW, "Integral gain of the d-axis current loop.";
+        DGainId: f32, RW, "Derivative gain of the d-axis current loop.";
+        PGainIq: f32, RW, "Proportional gain of the q-axis current loop.";
+        IGainIq: f32, RW, "Integral gain of the q-axis current loop.";
+        DGainIq: f32, RW, "Derivative gain of the q-axis current loop.";
+        PGainVel: f32, RW, "Proportional gain of the velocity loop.";
+        IGainVel: f32, RW, "Integral gain of the velocity loop.";
+        DGainVel: f32, RW, "Derivative gain of the velocity loop.";
+        PGainPos: f32, RW, "Proportional gain of the position loop.";
+        IGainPos: f32, RW, "Integral gain of the position loop.";
+        DGainPos: f32, RW, "Derivative gain of the position loop.";
+        PGainForce: f32, RW, "Proportional gain of the force loop.";
+        IGainForce: f32, RW, "Integral gain of the force loop.";
+        DGainForce: f32, RW, "Derivative gain of the force loop.";
+        LimitAccMax: f32, RW, "The maximum acceleration.", AngularAcceleration, angular_acceleration::radian_per_second_squared, "rad/s²";
+        LimitIMax: f32, RW, "The maximum current.", ElectricCurrent, electric_current::ampere, "A";
+        LimitVelMax: f32, RW, "The maximum velocity.", AngularVelocity, angular_velocity::radian_per_second, "rad/s";
+        LimitPosMin: f32, RW, "The lower joint limit.", Angle, angle::radian, "rad";
+        LimitPosMax: f32, RW, "The upper joint limit.", Angle, angle::radian, "rad";
+        MinVoltage: f32, RW, "The minimum input voltage.", ElectricPotential, electric_potential::volt, "V";
+        MaxVoltage: f32, RW, "The maximum input voltage.", ElectricPotential, electric_potential::volt, "V";
+        WatchdogTimeout: u32, RW, "The timeout of the communication watchdog in torque mode, 0 disables the watchdog.";
+        TempLimitLow: f32, RW, "The temperature above which the motor starts to limit its power.", ThermodynamicTemperature, thermodynamic_temperature::degree_celsius, "°C";
+        TempLimitHigh: f32, RW, "The temperature above which the motor shuts down.", ThermodynamicTemperature, thermodynamic_temperature::degree_celsius, "°C";
+        ReturnTimeDelay: u32, RW, "The delay before the motor sends its reply.";
+    });
 }
 
 /// Structs representing each status register.
@@ -179,20 +490,40 @@ pub mod config {
 pub mod status {
     use super::*;
     use crate::Response;
-    use crate::error::{BufferTooSmallError, InvalidMessage, TransferError, WriteError};
+    use crate::error::{BufferTooSmallError, InvalidMessage, TransferError};
     use crate::protocol::StatusRegister;
-    register!(StatusRegister::TorqueEnable, u32, RW);
-    register!(StatusRegister::HomingComplete, f32, RW);
-    register!(StatusRegister::GoalId, f32, RW);
-    register!(StatusRegister::GoalIq, f32, RW);
-    register!(StatusRegister::GoalVel, f32, RW);
-    register!(StatusRegister::GoalPos, f32, RW);
-    register!(StatusRegister::PresentId, f32, RO);
-    register!(StatusRegister::PresentIq, f32, RO);
-    register!(StatusRegister::PresentVel, f32, RO);
-    register!(StatusRegister::PresentPos, f32, RO);
-    register!(StatusRegister::InputVoltage, f32, RO);
-    register!(StatusRegister::WindingTemp, f32, RO);
-    register!(StatusRegister::PowerstageTemp, f32, RO);
-    register!(StatusRegister::IcTemp, f32, RO);
+    register_bank!(StatusRegister {
+        TorqueEnable: TorqueState, RW, "Enables or disables torque, or triggers an ESTOP.";
+        HomingComplete: f32, RW, "Whether homing has completed.";
+        GoalId: f32, RW, "The d-axis current goal.", ElectricCurrent, electric_current::ampere, "A";
+        GoalIq: f32, RW, "The q-axis current goal.", ElectricCurrent, electric_current::ampere, "A";
+        GoalVel: f32, RW, "The velocity goal.", AngularVelocity, angular_velocity::radian_per_second, "rad/s";
+        GoalPos: f32, RW, "The position goal.", Angle, angle::radian, "rad";
+        PresentId: f32, RO, "The present d-axis current.", ElectricCurrent, electric_current::ampere, "A";
+        PresentIq: f32, RO, "The present q-axis current.", ElectricCurrent, electric_current::ampere, "A";
+        PresentVel: f32, RO, "The present velocity.", AngularVelocity, angular_velocity::radian_per_second, "rad/s";
+        PresentPos: f32, RO, "The present position.", Angle, angle::radian, "rad";
+        InputVoltage: f32, RO, "The present input voltage.", ElectricPotential, electric_potential::volt, "V";
+        WindingTemp: f32, RO, "The present winding temperature.", ThermodynamicTemperature, thermodynamic_temperature::degree_celsius, "°C";
+        PowerstageTemp: f32, RO, "The present power stage temperature.", ThermodynamicTemperature, thermodynamic_temperature::degree_celsius, "°C";
+        IcTemp: f32, RO, "The present IC temperature.", ThermodynamicTemperature, thermodynamic_temperature::degree_celsius, "°C";
+    });
+}
+
+/// The metadata of every register, config registers first, each bank in address order.
+pub const REGISTERS: &[RegisterInfo] =
+    &concat_registers::<{ config::REGISTERS.len() + status::REGISTERS.len() }>(config::REGISTERS, status::REGISTERS);
+
+const fn concat_registers<const N: usize>(first: &[RegisterInfo], second: &[RegisterInfo]) -> [RegisterInfo; N] {
+    let mut registers = [first[0]; N];
+    let mut i = 1;
+    while i < first.len() {
+        registers[i] = first[i];
+        i += 1;
+    }
+    while i < N {
+        registers[i] = second[i - first.len()];
+        i += 1;
+    }
+    registers
 }
@@ -1,3 +1,4 @@
+use crate::protocol::REGISTER_BYTES;
 use crate::protocol::motor_error::ErrorFlags;
 
 /// A response from a motor.
@@ -6,20 +7,21 @@ use crate::protocol::motor_error::ErrorFlags;
 /// including the `motor_id` and `alert`.
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Response<T> {
     /// The motor that sent the response.
     pub motor_id: u8,
 
-    /// The motor returns an [`ErrorFlags`] byte with every response. If the flag contains any errors an Err is returned, or any warnings this field is Some(_)
+    /// The motor returns an [`ErrorFlags`] byte with every response.
+    ///
+    /// With the default [`crate::ErrorPolicy::Strict`], responses containing any [`crate::ERROR_FLAGS`] are
+    /// returned as an error instead, so this field only holds [`crate::WARNING_FLAGS`].
     pub warning: ErrorFlags,
 
     /// The data from the motor.
     pub data: T,
 }
 
-/// Bytes per register value on the wire (4 little-endian bytes).
-const REGISTER_BYTES: usize = 4;
-
 impl<T: AsRef<[u8]>> Response<T> {
     /// Decode the `index`th 4-byte register of the reply data as an `f32`.
     ///
@@ -0,0 +1,73 @@
+#[cfg(not(feature = "defmt"))]
+use bitflags::bitflags;
+use core::time::Duration;
+#[cfg(feature = "defmt")]
+use defmt::bitflags;
+
+bitflags! {
+    /// The kinds of failed transfers that are retried by a [`RetryPolicy`].
+    pub struct RetryOn: u8 {
+        /// The motor did not reply before the deadline.
+        const TIMEOUT = 0b001;
+        /// The reply had an invalid checksum.
+        const INVALID_CHECKSUM = 0b010;
+        /// The reply came from a different motor than the instruction was sent to.
+        const INVALID_PACKET_ID = 0b100;
+    }
+}
+
+/// How a [`crate::Bus`] retries single motor transfers that failed.
+///
+/// Retries apply to reads, pings, acknowledged writes, [`crate::Bus::save_config`] and
+/// [`crate::Bus::set_absolute_position`]. Bulk transfers are never retried.
+/// Every failed attempt that is retried is logged as a warning.
+///
+/// The default policy makes a single attempt, so nothing is retried.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one.
+    ///
+    /// A value of 0 is treated as 1.
+    pub max_attempts: u8,
+    /// The time to wait before sending the instruction again.
+    ///
+    /// Any reply that arrives while waiting is discarded.
+    pub backoff: Duration,
+    /// The kinds of failures that are retried.
+    pub retry_on: RetryOn,
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub const NEVER: Self = Self {
+        max_attempts: 1,
+        backoff: Duration::ZERO,
+        retry_on: RetryOn::empty(),
+    };
+
+    /// A policy that makes up to `max_attempts` attempts without backoff, retrying all [`RetryOn`] kinds.
+    pub const fn attempts(max_attempts: u8) -> Self {
+        Self {
+            max_attempts,
+            backoff: Duration::ZERO,
+            retry_on: RetryOn::all(),
+        }
+    }
+
+    /// Set the time to wait before sending the instruction again.
+    pub const fn with_backoff(self, backoff: Duration) -> Self {
+        Self { backoff, ..self }
+    }
+
+    /// Set the kinds of failures that are retried.
+    pub const fn with_retry_on(self, retry_on: RetryOn) -> Self {
+        Self { retry_on, ..self }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::NEVER
+    }
+}
@@ -0,0 +1,37 @@
+use crate::error::InvalidRegisterValue;
+
+/// The torque state of a motor, stored in the [`crate::StatusRegister::TorqueEnable`] register.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum TorqueState {
+    /// Torque is disabled and the motor is free to move. Config registers can only be written in this state.
+    Disabled = 0,
+    /// Torque is enabled and the motor follows its goal registers.
+    Enabled = 1,
+    /// Emergency stop, torque is disabled and [`crate::ErrorFlags::WATCHDOG_ESTOP`] is raised.
+    Estop = 3,
+}
+
+impl TryFrom<u32> for TorqueState {
+    type Error = InvalidRegisterValue;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Disabled),
+            1 => Ok(Self::Enabled),
+            3 => Ok(Self::Estop),
+            _ => Err(InvalidRegisterValue {
+                type_name: "TorqueState",
+                value,
+            }),
+        }
+    }
+}
+
+impl From<TorqueState> for u32 {
+    fn from(state: TorqueState) -> Self {
+        state as u32
+    }
+}
@@ -21,6 +21,9 @@ declare_serial2_module!();
 #[super::only_async]
 declare_serial2_module!();
 
+#[cfg(feature = "alloc")]
+mod sim;
+
 /// [`SerialPort`]s are used to communicate with the hardware by reading and writing data.
 ///
 /// The implementor of the trait must also configure the serial line to use 8 bits characters, 1 stop bit, no parity and no flow control.
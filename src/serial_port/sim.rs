@@ -0,0 +1,93 @@
+//! Trait implementations for the simulated bus and fault injector in [`crate::sim`].
+
+use core::time::Duration;
+
+use crate::sim::{FaultInjector, SimBus, SimError};
+
+#[super::super::bisync]
+impl super::SerialPort for SimBus {
+    type Error = SimError;
+
+    type Instant = Duration;
+
+    fn baud_rate(&self) -> Result<u32, Self::Error> {
+        Ok(self.baud_rate)
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Self::Error> {
+        self.baud_rate = baud_rate;
+        Ok(())
+    }
+
+    fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+        self.discard_replies();
+        Ok(())
+    }
+
+    async fn read(&mut self, buffer: &mut [u8], deadline: &Self::Instant) -> Result<usize, Self::Error> {
+        self.receive(buffer, *deadline)
+    }
+
+    async fn write_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
+        self.transmit(buffer);
+        Ok(())
+    }
+
+    fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+        self.now() + timeout
+    }
+
+    fn is_timeout_error(error: &Self::Error) -> bool {
+        matches!(error, SimError::Timeout)
+    }
+}
+
+#[super::super::bisync]
+impl<P: super::SerialPort> super::SerialPort for FaultInjector<P> {
+    type Error = P::Error;
+
+    type Instant = P::Instant;
+
+    fn baud_rate(&self) -> Result<u32, Self::Error> {
+        self.inner.baud_rate()
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Self::Error> {
+        self.inner.set_baud_rate(baud_rate)
+    }
+
+    fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+        self.discard();
+        self.inner.discard_input_buffer()
+    }
+
+    async fn read(&mut self, buffer: &mut [u8], deadline: &Self::Instant) -> Result<usize, Self::Error> {
+        let mut chunk = [0; 64];
+        loop {
+            if let Some(read) = self.serve(buffer) {
+                return Ok(read);
+            }
+            match self.inner.read(&mut chunk, deadline).await {
+                Ok(read) => self.accept(&chunk[..read]),
+                Err(e) => {
+                    if P::is_timeout_error(&e) {
+                        self.release_delayed();
+                    }
+                    return Err(e);
+                },
+            }
+        }
+    }
+
+    async fn write_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
+        self.inner.write_all(buffer).await
+    }
+
+    fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+        self.inner.make_deadline(timeout)
+    }
+
+    fn is_timeout_error(error: &Self::Error) -> bool {
+        P::is_timeout_error(error)
+    }
+}
@@ -0,0 +1,317 @@
+//! A cloneable handle to share one asynchronous [`Bus`] between several tasks.
+//!
+//! Every method of [`Bus`] takes `&mut self`, so only one task can talk to the motors at a time.
+//! [`SharedBus`] hands the bus to one task at a time through [`SharedBus::lock`], which returns a guard
+//! that dereferences to the [`Bus`]. While a task holds the guard, no other task can send a packet,
+//! so an instruction and its replies are never interleaved with those of another task.
+//! A task can also hold the guard across several transfers to make them a single atomic exchange.
+//!
+//! Waiting tasks get the bus in order of their [`Priority`], and in the order they started waiting within
+//! the same priority. This lets a control loop jump ahead of a telemetry logger or a diagnostics endpoint.
+
+use core::future::Future;
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::Bus;
+use super::bus::DefaultBuffer;
+
+/// The priority of a task waiting for a [`SharedBus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Priority {
+    /// For tasks that can wait, such as logging or diagnostics.
+    #[default]
+    Normal,
+    /// For real-time commands, such as a control loop. Served before any [`Priority::Normal`] task.
+    RealTime,
+}
+
+/// A cloneable handle to an asynchronous [`Bus`], shared between tasks.
+///
+/// [`SharedBus::lock`] waits until no other task holds the bus, and returns a guard that dereferences to the [`Bus`].
+/// Instructions and their replies are never interleaved with those of another task, and a task can keep the
+/// guard across several transfers to make them one atomic exchange. Waiting tasks get the bus in order of their
+/// [`Priority`], and in the order they started waiting within the same priority.
+pub struct SharedBus<SerialPort, Buffer = DefaultBuffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    state: Arc<Mutex<State<SerialPort, Buffer>>>,
+}
+
+/// The state behind a [`SharedBus`].
+struct State<SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// The bus, or `None` while a [`SharedBusGuard`] holds it.
+    bus: Option<Bus<SerialPort, Buffer>>,
+    /// The tasks waiting for the bus.
+    waiters: Vec<Waiter>,
+    /// The ticket of the next task to start waiting.
+    next_ticket: u64,
+}
+
+/// A task waiting for the bus.
+struct Waiter {
+    ticket: u64,
+    priority: Priority,
+    waker: Waker,
+}
+
+impl<SerialPort, Buffer> State<SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// The index of the waiter to serve next: the highest priority, then the earliest ticket.
+    fn next_waiter(&self) -> Option<usize> {
+        self.waiters
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, waiter)| (core::cmp::Reverse(waiter.priority), waiter.ticket))
+            .map(|(index, _)| index)
+    }
+
+    /// Wake the waiter to serve next, if the bus is free.
+    fn wake_next(&self) {
+        if self.bus.is_none() {
+            return;
+        }
+        if let Some(index) = self.next_waiter() {
+            self.waiters[index].waker.wake_by_ref();
+        }
+    }
+}
+
+impl<SerialPort, Buffer> SharedBus<SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Share a bus between tasks.
+    pub fn new(bus: Bus<SerialPort, Buffer>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                bus: Some(bus),
+                waiters: Vec::new(),
+                next_ticket: 0,
+            })),
+        }
+    }
+
+    /// Wait for exclusive access to the bus with [`Priority::Normal`].
+    pub fn lock(&self) -> Lock<'_, SerialPort, Buffer> {
+        self.lock_with_priority(Priority::Normal)
+    }
+
+    /// Wait for exclusive access to the bus with [`Priority::RealTime`].
+    pub fn lock_realtime(&self) -> Lock<'_, SerialPort, Buffer> {
+        self.lock_with_priority(Priority::RealTime)
+    }
+
+    /// Wait for exclusive access to the bus with the given priority.
+    ///
+    /// The bus is released when the returned guard is dropped.
+    pub fn lock_with_priority(&self, priority: Priority) -> Lock<'_, SerialPort, Buffer> {
+        Lock {
+            shared: self,
+            priority,
+            ticket: None,
+        }
+    }
+
+    /// Get the bus back, if this is the last handle to it.
+    pub fn try_into_inner(self) -> Result<Bus<SerialPort, Buffer>, Self> {
+        match Arc::try_unwrap(self.state) {
+            // No guard can exist without a handle, so the bus is always present here.
+            Ok(state) => Ok(unpoison(state.into_inner()).bus.unwrap()),
+            Err(state) => Err(Self { state }),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State<SerialPort, Buffer>> {
+        unpoison(self.state.lock())
+    }
+}
+
+/// The state holds no invariants a panic could break, so a poisoned lock is still usable.
+fn unpoison<T>(result: Result<T, std::sync::PoisonError<T>>) -> T {
+    result.unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+impl<SerialPort, Buffer> Clone for SharedBus<SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<SerialPort, Buffer> core::fmt::Debug for SharedBus<SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let state = self.state();
+        f.debug_struct("SharedBus")
+            .field("locked", &state.bus.is_none())
+            .field("waiters", &state.waiters.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A future waiting for exclusive access to a [`SharedBus`], see [`SharedBus::lock`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Lock<'a, SerialPort, Buffer = DefaultBuffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    shared: &'a SharedBus<SerialPort, Buffer>,
+    priority: Priority,
+    /// The place in the queue, once the task has started waiting.
+    ticket: Option<u64>,
+}
+
+impl<'a, SerialPort, Buffer> Future for Lock<'a, SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    type Output = SharedBusGuard<'a, SerialPort, Buffer>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut state = this.shared.state();
+        let index = this
+            .ticket
+            .and_then(|ticket| state.waiters.iter().position(|waiter| waiter.ticket == ticket));
+        let first = match index {
+            Some(index) => state.next_waiter() == Some(index),
+            None => state.waiters.is_empty(),
+        };
+        if first && state.bus.is_some() {
+            if let Some(index) = index {
+                state.waiters.swap_remove(index);
+            }
+            this.ticket = None;
+            return Poll::Ready(SharedBusGuard {
+                shared: this.shared,
+                bus: state.bus.take(),
+            });
+        }
+
+        match index {
+            Some(index) => state.waiters[index].waker.clone_from(cx.waker()),
+            None => {
+                let ticket = state.next_ticket;
+                state.next_ticket += 1;
+                state.waiters.push(Waiter {
+                    ticket,
+                    priority: this.priority,
+                    waker: cx.waker().clone(),
+                });
+                this.ticket = Some(ticket);
+            },
+        }
+        // The bus may be free for a waiter that has not been woken yet, such as one that queued behind
+        // this task and has a higher priority.
+        state.wake_next();
+        Poll::Pending
+    }
+}
+
+impl<SerialPort, Buffer> Drop for Lock<'_, SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn drop(&mut self) {
+        let Some(ticket) = self.ticket else { return };
+        let mut state = self.shared.state();
+        if let Some(index) = state.waiters.iter().position(|waiter| waiter.ticket == ticket) {
+            state.waiters.swap_remove(index);
+        }
+        // This task may have been woken to take the bus, so pass the wake-up on.
+        state.wake_next();
+    }
+}
+
+impl<SerialPort, Buffer> core::fmt::Debug for Lock<'_, SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Lock")
+            .field("priority", &self.priority)
+            .field("ticket", &self.ticket)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Exclusive access to the [`Bus`] of a [`SharedBus`], released when dropped.
+pub struct SharedBusGuard<'a, SerialPort, Buffer = DefaultBuffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    shared: &'a SharedBus<SerialPort, Buffer>,
+    /// Always `Some` until dropped.
+    bus: Option<Bus<SerialPort, Buffer>>,
+}
+
+impl<SerialPort, Buffer> Deref for SharedBusGuard<'_, SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    type Target = Bus<SerialPort, Buffer>;
+
+    fn deref(&self) -> &Self::Target {
+        self.bus.as_ref().unwrap()
+    }
+}
+
+impl<SerialPort, Buffer> DerefMut for SharedBusGuard<'_, SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.bus.as_mut().unwrap()
+    }
+}
+
+impl<SerialPort, Buffer> Drop for SharedBusGuard<'_, SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn drop(&mut self) {
+        let mut state = self.shared.state();
+        state.bus = self.bus.take();
+        state.wake_next();
+    }
+}
+
+impl<SerialPort, Buffer> core::fmt::Debug for SharedBusGuard<'_, SerialPort, Buffer>
+where
+    SerialPort: super::SerialPort + core::fmt::Debug,
+    Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("SharedBusGuard").field(&**self).finish()
+    }
+}
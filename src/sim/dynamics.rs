@@ -0,0 +1,185 @@
+//! First-order actuator dynamics for [`SimMotor`].
+//!
+//! Each motor runs the controller selected by its `Mode` config register, using the gains from the config
+//! table. The controller output is a q-axis current command, which the current loop follows with a first-order
+//! lag. The resulting torque drives a single rotating inertia with viscous damping, and the winding temperature
+//! rises with the resistive losses (`I² R`) and cools towards the ambient temperature.
+//!
+//! | Mode | Controller                                                                                 |
+//! |------|--------------------------------------------------------------------------------------------|
+//! | 0    | Torque: `GoalIq` is the current command.                                                   |
+//! | 1    | Velocity: PI on `GoalVel` using the `*GainVel` gains.                                      |
+//! | 2    | Position: PID on `GoalPos` using the `*GainPos` gains.                                     |
+//! | 3    | Force: impedance around `GoalPos`/`GoalVel` using the `*GainForce` gains, plus `GoalIq`.   |
+//! | 4    | Direct force PID: PID on `GoalPos` using the `*GainForce` gains.                           |
+
+use super::SimMotor;
+use crate::protocol::{ConfigRegister, StatusRegister};
+use crate::{ErrorFlags, OperatingMode};
+
+/// The physical parameters of a simulated motor and its load.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotorModel {
+    /// Rotor and load inertia, in kg m².
+    pub inertia: f32,
+    /// Viscous damping, in N m s / rad.
+    pub damping: f32,
+    /// Torque constant, in N m / A.
+    pub torque_constant: f32,
+    /// Time constant of the current loop, in seconds.
+    pub current_time_constant: f32,
+    /// Winding resistance, in ohm.
+    pub winding_resistance: f32,
+    /// Thermal resistance from the winding to ambient, in K / W.
+    pub thermal_resistance: f32,
+    /// Thermal capacitance of the winding, in J / K.
+    pub thermal_capacitance: f32,
+    /// Ambient temperature, in °C.
+    pub ambient_temperature: f32,
+}
+
+impl Default for MotorModel {
+    fn default() -> Self {
+        Self {
+            inertia: 1e-3,
+            damping: 1e-3,
+            torque_constant: 0.1,
+            current_time_constant: 5e-4,
+            winding_resistance: 0.2,
+            thermal_resistance: 2.0,
+            thermal_capacitance: 30.0,
+            ambient_temperature: 25.0,
+        }
+    }
+}
+
+/// Internal state of the motion controller.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct Controller {
+    /// The mode the integrator was accumulated in.
+    mode: u32,
+    /// The integral of the controller error.
+    integral: f32,
+}
+
+impl SimMotor {
+    /// Advance the motor dynamics by `dt` seconds.
+    pub(super) fn step(&mut self, dt: f32) {
+        let model = self.model;
+        let position = self.status_f32(StatusRegister::PresentPos);
+        let velocity = self.status_f32(StatusRegister::PresentVel);
+        let current = self.status_f32(StatusRegister::PresentIq);
+
+        let mut command = if self.torque_enabled() {
+            self.current_command(position, velocity, dt)
+        } else {
+            self.controller = Controller::default();
+            0.0
+        };
+        let limit = self.config_f32(ConfigRegister::LimitIMax);
+        if limit > 0.0 {
+            command = command.clamp(-limit, limit);
+        }
+
+        // Current loop as a first-order lag, discretised so it stays stable for any step size.
+        let current = current + (command - current) * (dt / (model.current_time_constant + dt));
+        let torque = model.torque_constant * current;
+        let velocity = velocity + (torque - model.damping * velocity) / model.inertia * dt;
+        let position = position + velocity * dt;
+
+        let goal_id = self.status_f32(StatusRegister::GoalId);
+        let present_id = self.status_f32(StatusRegister::PresentId);
+        let present_id = present_id + (goal_id - present_id) * (dt / (model.current_time_constant + dt));
+
+        let temperature = self.status_f32(StatusRegister::WindingTemp);
+        let heating = (current * current + present_id * present_id) * model.winding_resistance;
+        let cooling = (temperature - model.ambient_temperature) / model.thermal_resistance;
+        let temperature = temperature + (heating - cooling) / model.thermal_capacitance * dt;
+
+        self.set_status_f32(StatusRegister::PresentIq, current);
+        self.set_status_f32(StatusRegister::PresentId, present_id);
+        self.set_status_f32(StatusRegister::PresentVel, velocity);
+        self.set_status_f32(StatusRegister::PresentPos, position);
+        self.set_status_f32(StatusRegister::WindingTemp, temperature);
+        self.check_limits(position, temperature);
+    }
+
+    /// Run the controller for the configured mode, returning the q-axis current command.
+    fn current_command(&mut self, position: f32, velocity: f32, dt: f32) -> f32 {
+        let mode = self.config_u32(ConfigRegister::Mode);
+        if self.controller.mode != mode {
+            self.controller = Controller { mode, integral: 0.0 };
+        }
+        let goal_pos = self.status_f32(StatusRegister::GoalPos);
+        let goal_vel = self.status_f32(StatusRegister::GoalVel);
+        let goal_iq = self.status_f32(StatusRegister::GoalIq);
+
+        let Ok(mode) = OperatingMode::try_from(mode) else {
+            return 0.0;
+        };
+        match mode {
+            OperatingMode::Torque => goal_iq,
+            OperatingMode::Velocity => {
+                let limit = self.config_f32(ConfigRegister::LimitVelMax);
+                let goal_vel = if limit > 0.0 {
+                    goal_vel.clamp(-limit, limit)
+                } else {
+                    goal_vel
+                };
+                let error = goal_vel - velocity;
+                self.controller.integral += error * dt;
+                self.config_f32(ConfigRegister::PGainVel) * error
+                    + self.config_f32(ConfigRegister::IGainVel) * self.controller.integral
+            },
+            OperatingMode::Position => {
+                let error = goal_pos - position;
+                self.controller.integral += error * dt;
+                self.config_f32(ConfigRegister::PGainPos) * error
+                    + self.config_f32(ConfigRegister::IGainPos) * self.controller.integral
+                    - self.config_f32(ConfigRegister::DGainPos) * velocity
+            },
+            OperatingMode::Force => {
+                goal_iq
+                    + self.config_f32(ConfigRegister::PGainForce) * (goal_pos - position)
+                    + self.config_f32(ConfigRegister::DGainForce) * (goal_vel - velocity)
+            },
+            OperatingMode::DirectForce => {
+                let error = goal_pos - position;
+                self.controller.integral += error * dt;
+                self.config_f32(ConfigRegister::PGainForce) * error
+                    + self.config_f32(ConfigRegister::IGainForce) * self.controller.integral
+                    - self.config_f32(ConfigRegister::DGainForce) * velocity
+            },
+        }
+    }
+
+    /// Raise or clear the flags for configured position and temperature limits.
+    fn check_limits(&mut self, position: f32, temperature: f32) {
+        let min = self.config_f32(ConfigRegister::LimitPosMin);
+        let max = self.config_f32(ConfigRegister::LimitPosMax);
+        if min < max {
+            let flags = self.error_flags();
+            self.set_error_flags(if position < min || position > max {
+                flags | ErrorFlags::JOINT_LIMIT
+            } else {
+                flags - ErrorFlags::JOINT_LIMIT
+            });
+        }
+
+        let low = self.config_f32(ConfigRegister::TempLimitLow);
+        if low > 0.0 {
+            let flags = self.error_flags();
+            self.set_error_flags(if temperature > low {
+                flags | ErrorFlags::OVERHEAT
+            } else {
+                flags - ErrorFlags::OVERHEAT
+            });
+        }
+
+        let high = self.config_f32(ConfigRegister::TempLimitHigh);
+        if high > 0.0 && temperature > high {
+            // Over the high limit the motor shuts down.
+            self.set_status_u32(StatusRegister::TorqueEnable, 0);
+        }
+    }
+}
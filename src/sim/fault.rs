@@ -0,0 +1,236 @@
+//! Fault injection for testing how the host handles a bad bus.
+
+use crate::ErrorFlags;
+use crate::checksum;
+use crate::protocol::{PACKET_ERROR, PACKET_ID, PACKET_LEN};
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use super::{HEADER_PREFIX, HEADER_SIZE};
+
+/// A fault applied to a single reply packet by a [`FaultInjector`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Fault {
+    /// Leave the reply untouched, useful to target a later reply of a bulk transfer.
+    Pass,
+    /// Drop the last `n` bytes of the reply.
+    DropBytes(usize),
+    /// Invert the checksum of the reply.
+    CorruptChecksum,
+    /// Send the given bytes right before the reply.
+    LeadingGarbage(Vec<u8>),
+    /// Deliver the reply in chunks of at most `n` bytes, one chunk per read.
+    SplitReads(usize),
+    /// Hold the reply back until the wrapped port reports a timeout, so it arrives after the deadline.
+    Delay,
+    /// Set error flags in the error byte of the reply, with a valid checksum.
+    SetErrorFlags(ErrorFlags),
+    /// Replace the motor ID of the reply, with a valid checksum.
+    ChangeId(u8),
+}
+
+/// A chunk of bytes ready to be read by the host.
+#[derive(Debug)]
+struct Segment {
+    /// The bytes that have not been read yet.
+    bytes: VecDeque<u8>,
+    /// The maximum number of bytes of this segment to return from a single read.
+    max_read: Option<usize>,
+}
+
+/// A [`SerialPort`](crate::SerialPort) adapter that injects faults into the replies of the wrapped port.
+///
+/// Replies read from the wrapped port are split into packets, and each packet consumes the next queued
+/// [`Fault`]. Packets are passed through untouched once the queue is empty. Bytes that are written are
+/// always passed through untouched.
+///
+/// ```
+/// use ww_bear::Bus;
+/// use ww_bear::sim::{Fault, FaultInjector, SimBus};
+///
+/// let mut bus = Bus::new(FaultInjector::new(SimBus::with_motors(&[1]))).unwrap();
+/// bus.serial_port().inject(Fault::CorruptChecksum);
+/// assert!(bus.read_present_pos(1).is_err());
+/// assert!(bus.read_present_pos(1).is_ok());
+/// ```
+#[derive(Debug)]
+pub struct FaultInjector<P> {
+    /// The wrapped port.
+    pub(crate) inner: P,
+    /// Faults to apply to the next replies, in order.
+    faults: VecDeque<Fault>,
+    /// Bytes read from the wrapped port that do not form a complete packet yet.
+    staged: Vec<u8>,
+    /// Bytes ready to be read by the host.
+    output: VecDeque<Segment>,
+    /// Replies held back by [`Fault::Delay`].
+    delayed: Vec<u8>,
+}
+
+impl<P> FaultInjector<P> {
+    /// Wrap a serial port.
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            faults: VecDeque::new(),
+            staged: Vec::new(),
+            output: VecDeque::new(),
+            delayed: Vec::new(),
+        }
+    }
+
+    /// Queue a fault to apply to the next reply that has no fault assigned yet.
+    pub fn inject(&mut self, fault: Fault) {
+        self.faults.push_back(fault);
+    }
+
+    /// The number of queued faults that have not been applied yet.
+    pub fn pending_faults(&self) -> usize {
+        self.faults.len()
+    }
+
+    /// Remove all queued faults.
+    pub fn clear_faults(&mut self) {
+        self.faults.clear();
+    }
+
+    /// Get a reference to the wrapped port.
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped port.
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.inner
+    }
+
+    /// Unwrap the port, dropping any queued faults.
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+
+    /// Copy ready bytes into `buffer`, returning `None` if there are none.
+    pub(crate) fn serve(&mut self, buffer: &mut [u8]) -> Option<usize> {
+        self.output.front()?;
+        let mut read = 0;
+        while read < buffer.len() {
+            let Some(segment) = self.output.front_mut() else { break };
+            // A split segment is always served on its own.
+            let split = segment.max_read.is_some();
+            if split && read > 0 {
+                break;
+            }
+            let n = segment
+                .bytes
+                .len()
+                .min(segment.max_read.unwrap_or(usize::MAX))
+                .min(buffer.len() - read);
+            for (dst, src) in buffer[read..read + n].iter_mut().zip(segment.bytes.drain(..n)) {
+                *dst = src;
+            }
+            read += n;
+            if segment.bytes.is_empty() {
+                self.output.pop_front();
+            }
+            if split {
+                break;
+            }
+        }
+        Some(read)
+    }
+
+    /// Accept bytes read from the wrapped port, applying faults to every complete packet.
+    pub(crate) fn accept(&mut self, bytes: &[u8]) {
+        self.staged.extend_from_slice(bytes);
+        loop {
+            let start = find_header(&self.staged);
+            if start > 0 {
+                let garbage: Vec<u8> = self.staged.drain(..start).collect();
+                self.push_output(garbage, None);
+            }
+            if self.staged.len() <= HEADER_SIZE {
+                return;
+            }
+            let packet_len = HEADER_SIZE + self.staged[PACKET_LEN] as usize;
+            if self.staged.len() < packet_len {
+                return;
+            }
+            let packet: Vec<u8> = self.staged.drain(..packet_len).collect();
+            self.apply_fault(packet);
+        }
+    }
+
+    /// Make replies held back by [`Fault::Delay`] available, after the host has given up on them.
+    pub(crate) fn release_delayed(&mut self) {
+        if !self.delayed.is_empty() {
+            let delayed = core::mem::take(&mut self.delayed);
+            self.push_output(delayed, None);
+        }
+    }
+
+    /// Throw away all bytes that have not been read yet.
+    pub(crate) fn discard(&mut self) {
+        self.staged.clear();
+        self.output.clear();
+        self.delayed.clear();
+    }
+
+    fn apply_fault(&mut self, mut packet: Vec<u8>) {
+        let fault = self.faults.pop_front().unwrap_or(Fault::Pass);
+        log::debug!("injecting {:?} into reply {:02X?}", fault, packet);
+        match fault {
+            Fault::Pass => self.push_output(packet, None),
+            Fault::DropBytes(n) => {
+                packet.truncate(packet.len().saturating_sub(n));
+                self.push_output(packet, None);
+            },
+            Fault::CorruptChecksum => {
+                let last = packet.len() - 1;
+                packet[last] = !packet[last];
+                self.push_output(packet, None);
+            },
+            Fault::LeadingGarbage(garbage) => {
+                self.push_output(garbage, None);
+                self.push_output(packet, None);
+            },
+            Fault::SplitReads(n) => self.push_output(packet, Some(n.max(1))),
+            Fault::Delay => self.delayed.extend_from_slice(&packet),
+            Fault::SetErrorFlags(flags) => {
+                packet[PACKET_ERROR] |= flags.bits();
+                update_checksum(&mut packet);
+                self.push_output(packet, None);
+            },
+            Fault::ChangeId(motor_id) => {
+                packet[PACKET_ID] = motor_id;
+                update_checksum(&mut packet);
+                self.push_output(packet, None);
+            },
+        }
+    }
+
+    fn push_output(&mut self, bytes: Vec<u8>, max_read: Option<usize>) {
+        if bytes.is_empty() {
+            return;
+        }
+        self.output.push_back(Segment {
+            bytes: bytes.into(),
+            max_read,
+        });
+    }
+}
+
+/// Find the start of the first packet header, or the start of a trailing partial header.
+fn find_header(buffer: &[u8]) -> usize {
+    (0..buffer.len())
+        .find(|&i| {
+            let possible_prefix = HEADER_PREFIX.len().min(buffer.len() - i);
+            buffer[i..].starts_with(&HEADER_PREFIX[..possible_prefix])
+        })
+        .unwrap_or(buffer.len())
+}
+
+fn update_checksum(packet: &mut [u8]) {
+    let last = packet.len() - 1;
+    packet[last] = checksum::calculate_checksum(&packet[2..last]);
+}
@@ -0,0 +1,346 @@
+//! A simulated BEAR bus for testing without hardware.
+//!
+//! [`SimBus`] implements both the blocking [`crate::SerialPort`] and the [`crate::asynchronous::SerialPort`]
+//! traits, so it can be handed to [`crate::Bus::new`] (or [`crate::asynchronous::Bus::new`]) in place of a real
+//! serial port. Instruction packets written to it are parsed exactly as the firmware would, dispatched to the
+//! [`SimMotor`]s on the bus, and answered with correctly checksummed status packets.
+//!
+//! Each [`SimMotor`] keeps its own config and status register tables, which can be inspected or modified
+//! directly through [`SimBus::motor`] and [`SimBus::motor_mut`].
+//!
+//! While torque is enabled, the goal registers drive the present registers through a simple controller and
+//! inertia model (see [`MotorModel`]), so closed-loop code can be tested deterministically.
+//!
+//! The simulation runs on a virtual clock. Writing a packet advances the clock by the time the packet takes on
+//! the wire, and replies become readable once they would have been fully received. Reading with a deadline that
+//! passes before a reply arrives advances the clock to the deadline and returns [`SimError::Timeout`].
+//! Use [`SimBus::advance`] to let time pass between transfers, for example instead of sleeping.
+//!
+//! Motors only understand packets sent at the baud rate selected by their `BaudRate` config register (see
+//! [`crate::Baud`]), and ignore everything else.
+//!
+//! To test how the host copes with a bad bus, wrap any serial port in a [`FaultInjector`] and queue [`Fault`]s
+//! for the next replies.
+//!
+//! ```
+//! use ww_bear::Bus;
+//! use ww_bear::sim::SimBus;
+//!
+//! let mut bus = Bus::new(SimBus::with_motors(&[1, 2])).unwrap();
+//! bus.write_goal_pos(1, 1.5).unwrap();
+//! assert_eq!(bus.read_goal_pos(1).unwrap().data, 1.5);
+//! assert!(bus.ping(3).is_err());
+//! ```
+
+mod dynamics;
+pub use dynamics::MotorModel;
+mod fault;
+pub use fault::{Fault, FaultInjector};
+mod motor;
+pub use motor::SimMotor;
+
+use crate::Baud;
+use crate::checksum;
+use crate::protocol::{BROADCAST_ID, Instruction, PACKET_ID, PACKET_LEN};
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::time::Duration;
+use derive_more::{Display, Error};
+
+pub(crate) const HEADER_PREFIX: [u8; 2] = [0xFF, 0xFF];
+pub(crate) const HEADER_SIZE: usize = 4;
+
+/// The largest step used to integrate the motor dynamics.
+const MAX_TIME_STEP: Duration = Duration::from_micros(100);
+
+/// An error returned by the simulated serial port.
+#[derive(Debug, Display, Error, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum SimError {
+    /// No reply arrived before the deadline.
+    #[display("timed out waiting for a reply")]
+    Timeout,
+}
+
+/// A reply queued by a simulated motor, waiting to be read by the host.
+#[derive(Debug)]
+struct PendingReply {
+    /// The virtual time at which the last byte of the reply has arrived.
+    ready_at: Duration,
+    /// The encoded status packet.
+    packet: Vec<u8>,
+}
+
+/// A simulated serial bus with any number of [`SimMotor`]s attached.
+///
+/// See the [module documentation](self) for an overview.
+#[derive(Debug)]
+pub struct SimBus {
+    /// The motors attached to the bus.
+    motors: Vec<SimMotor>,
+    /// The baud rate of the host side of the bus.
+    pub(crate) baud_rate: u32,
+    /// The current virtual time.
+    now: Duration,
+    /// The time a motor takes to start replying after receiving an instruction.
+    response_latency: Duration,
+    /// Bytes written by the host that do not form a complete packet yet.
+    input: Vec<u8>,
+    /// Replies waiting to be read by the host, in the order they were sent.
+    replies: VecDeque<PendingReply>,
+    /// Bytes of the front reply that have already been read.
+    reply_offset: usize,
+}
+
+impl Default for SimBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimBus {
+    /// Create an empty bus running at 8 Mbaud.
+    pub fn new() -> Self {
+        Self {
+            motors: Vec::new(),
+            baud_rate: 8_000_000,
+            now: Duration::ZERO,
+            response_latency: Duration::from_micros(50),
+            input: Vec::new(),
+            replies: VecDeque::new(),
+            reply_offset: 0,
+        }
+    }
+
+    /// Create a bus with a default [`SimMotor`] for each of the given IDs.
+    pub fn with_motors(motor_ids: &[u8]) -> Self {
+        let mut bus = Self::new();
+        for &motor_id in motor_ids {
+            bus.add_motor(SimMotor::new(motor_id));
+        }
+        bus
+    }
+
+    /// Attach a motor to the bus.
+    pub fn add_motor(&mut self, motor: SimMotor) {
+        self.motors.push(motor);
+    }
+
+    /// Detach the motor with the given ID from the bus, returning it if it was present.
+    pub fn remove_motor(&mut self, motor_id: u8) -> Option<SimMotor> {
+        let index = self.motors.iter().position(|motor| motor.id() == motor_id)?;
+        Some(self.motors.remove(index))
+    }
+
+    /// Get the motor with the given ID.
+    pub fn motor(&self, motor_id: u8) -> Option<&SimMotor> {
+        self.motors.iter().find(|motor| motor.id() == motor_id)
+    }
+
+    /// Get a mutable reference to the motor with the given ID.
+    pub fn motor_mut(&mut self, motor_id: u8) -> Option<&mut SimMotor> {
+        self.motors.iter_mut().find(|motor| motor.id() == motor_id)
+    }
+
+    /// Iterate over all motors attached to the bus.
+    pub fn motors(&self) -> impl Iterator<Item = &SimMotor> {
+        self.motors.iter()
+    }
+
+    /// The current virtual time.
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Advance the virtual clock.
+    pub fn advance(&mut self, duration: Duration) {
+        self.advance_to(self.now + duration);
+    }
+
+    /// Get the time a motor takes to start replying after receiving an instruction.
+    pub fn response_latency(&self) -> Duration {
+        self.response_latency
+    }
+
+    /// Set the time a motor takes to start replying after receiving an instruction.
+    pub fn set_response_latency(&mut self, latency: Duration) {
+        self.response_latency = latency;
+    }
+
+    /// Advance the virtual clock to `time`, integrating the motor dynamics on the way.
+    fn advance_to(&mut self, time: Duration) {
+        while self.now < time {
+            let step = (time - self.now).min(MAX_TIME_STEP);
+            for motor in self.motors.iter_mut() {
+                motor.step(step.as_secs_f32());
+            }
+            self.now += step;
+        }
+    }
+
+    /// Accept bytes written by the host and process every complete packet.
+    pub(crate) fn transmit(&mut self, bytes: &[u8]) {
+        self.advance(crate::bus::message_transfer_time(bytes.len() as u32, self.baud_rate));
+        self.input.extend_from_slice(bytes);
+
+        loop {
+            // Drop leading bytes that can't be the start of a packet.
+            let start = self
+                .input
+                .windows(HEADER_PREFIX.len())
+                .position(|window| window == HEADER_PREFIX)
+                .unwrap_or(self.input.len().saturating_sub(1));
+            self.input.drain(..start);
+
+            if self.input.len() <= HEADER_SIZE {
+                return;
+            }
+            let packet_len = HEADER_SIZE + self.input[PACKET_LEN] as usize;
+            if self.input.len() < packet_len {
+                return;
+            }
+            let packet: Vec<u8> = self.input.drain(..packet_len).collect();
+            self.process_packet(&packet);
+        }
+    }
+
+    /// Read reply bytes that have arrived before the deadline.
+    pub(crate) fn receive(&mut self, buffer: &mut [u8], deadline: Duration) -> Result<usize, SimError> {
+        let ready_at = match self.replies.front() {
+            Some(reply) if reply.ready_at <= deadline => reply.ready_at,
+            _ => {
+                self.advance_to(deadline);
+                return Err(SimError::Timeout);
+            },
+        };
+        self.advance_to(ready_at);
+
+        let mut read = 0;
+        while read < buffer.len() {
+            let Some(reply) = self.replies.front() else { break };
+            if reply.ready_at > self.now {
+                break;
+            }
+            let remaining = &reply.packet[self.reply_offset..];
+            let n = remaining.len().min(buffer.len() - read);
+            buffer[read..read + n].copy_from_slice(&remaining[..n]);
+            read += n;
+            self.reply_offset += n;
+            if self.reply_offset == reply.packet.len() {
+                self.replies.pop_front();
+                self.reply_offset = 0;
+            }
+        }
+        Ok(read)
+    }
+
+    /// Throw away all replies that have not been read yet.
+    pub(crate) fn discard_replies(&mut self) {
+        self.replies.clear();
+        self.reply_offset = 0;
+    }
+
+    fn process_packet(&mut self, packet: &[u8]) {
+        // LEN counts the instruction and the checksum, so anything shorter can not be a packet.
+        if packet[PACKET_LEN] < 2 {
+            log::debug!("sim: dropping packet with invalid length: {:02X?}", packet);
+            return;
+        }
+        let checksum_index = packet.len() - 1;
+        if checksum::calculate_checksum(&packet[2..checksum_index]) != packet[checksum_index] {
+            log::debug!("sim: dropping packet with invalid checksum: {:02X?}", packet);
+            return;
+        }
+        let motor_id = packet[PACKET_ID];
+        let instruction = packet[4];
+        let parameters = &packet[5..checksum_index];
+
+        if instruction == Instruction::BulkComm as u8 {
+            if motor_id == BROADCAST_ID {
+                self.process_bulk(parameters);
+            }
+            return;
+        }
+
+        let baud_rate = self.baud_rate;
+        let mut replies = Vec::new();
+        for motor in self.motors.iter_mut() {
+            if motor.baud().map(Baud::bits_per_second) != Some(baud_rate) {
+                continue;
+            }
+            if motor_id == BROADCAST_ID || motor.id() == motor_id {
+                let reply = motor.process(instruction, parameters);
+                // Broadcast instructions are never answered.
+                if motor_id != BROADCAST_ID {
+                    replies.push(reply);
+                }
+            }
+        }
+        let mut ready_at = self.now + self.response_latency;
+        for reply in replies {
+            self.queue_reply(&mut ready_at, reply);
+        }
+    }
+
+    /// Process the parameters of a [`Instruction::BulkComm`] packet.
+    ///
+    /// The layout is `[motor_count, read_count << 4 | write_count, read_addrs.., write_addrs.., (id, data..)..]`.
+    fn process_bulk(&mut self, parameters: &[u8]) {
+        let Some((&motor_count, rest)) = parameters.split_first() else {
+            return;
+        };
+        let Some((&counts, rest)) = rest.split_first() else {
+            return;
+        };
+        let read_count = (counts >> 4) as usize;
+        let write_count = (counts & 0x0F) as usize;
+        if rest.len() < read_count + write_count {
+            return;
+        }
+        let (read_registers, rest) = rest.split_at(read_count);
+        let (write_registers, mut rows) = rest.split_at(write_count);
+
+        let mut ready_at = self.now + self.response_latency;
+        for _ in 0..motor_count {
+            let row_len = 1 + write_count * 4;
+            if rows.len() < row_len {
+                break;
+            }
+            let (row, rest) = rows.split_at(row_len);
+            rows = rest;
+            let baud_rate = self.baud_rate;
+            let Some(motor) = self
+                .motors
+                .iter_mut()
+                .find(|motor| motor.id() == row[0] && motor.baud().map(Baud::bits_per_second) == Some(baud_rate))
+            else {
+                continue;
+            };
+            let reply = motor.process_bulk(read_registers, write_registers, &row[1..]);
+            if read_count > 0 {
+                self.queue_reply(&mut ready_at, reply);
+            }
+        }
+    }
+
+    fn queue_reply(&mut self, ready_at: &mut Duration, packet: Vec<u8>) {
+        *ready_at += crate::bus::message_transfer_time(packet.len() as u32, self.baud_rate);
+        self.replies.push_back(PendingReply {
+            ready_at: *ready_at,
+            packet,
+        });
+    }
+}
+
+/// Encode a status packet: `FF FF id len error data.. checksum`.
+fn status_packet(motor_id: u8, error: u8, data: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(HEADER_SIZE + 2 + data.len());
+    packet.extend_from_slice(&HEADER_PREFIX);
+    packet.push(motor_id);
+    packet.push((data.len() + 2) as u8);
+    packet.push(error);
+    packet.extend_from_slice(data);
+    packet.push(checksum::calculate_checksum(&packet[2..]));
+    packet
+}
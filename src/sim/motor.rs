@@ -0,0 +1,297 @@
+use super::dynamics::{Controller, MotorModel};
+use super::status_packet;
+use crate::protocol::{ConfigRegister, Instruction, StatusRegister};
+use crate::{Baud, ErrorFlags, TorqueState};
+use alloc::vec::Vec;
+use core::f32::consts::{PI, TAU};
+
+/// Number of addresses in the config register table.
+const CONFIG_LEN: usize = ConfigRegister::ReturnTimeDelay as usize + 1;
+
+/// Number of addresses in the status register table.
+const STATUS_LEN: usize = StatusRegister::IcTemp as usize + 1;
+
+/// The first read-only status register. Everything from here on is measured by the motor.
+const FIRST_READ_ONLY_STATUS: u8 = StatusRegister::PresentId as u8;
+
+/// The firmware always sets the most significant bit of the error byte in its replies.
+const ERROR_BYTE_MARKER: u8 = 0x80;
+
+const PING: u8 = Instruction::Ping as u8;
+const READ_STAT: u8 = Instruction::ReadStat as u8;
+const WRITE_STAT: u8 = Instruction::WriteStat as u8;
+const READ_CFG: u8 = Instruction::ReadCfg as u8;
+const WRITE_CFG: u8 = Instruction::WriteCfg as u8;
+const SAVE_CFG: u8 = Instruction::SaveCfg as u8;
+const SET_ABS_POS: u8 = Instruction::SetAbsPos as u8;
+
+/// A single simulated BEAR motor.
+///
+/// Every register is stored as its raw 4 byte little-endian encoding, exactly as it travels on the wire.
+/// Use the `config_*` and `status_*` accessors to inspect or modify the register tables from a test.
+///
+/// While torque is enabled, the goal registers drive the present registers according to the configured mode
+/// and gains, see [`MotorModel`] for the physical model.
+#[derive(Debug, Clone)]
+pub struct SimMotor {
+    /// The live config register table.
+    config: [[u8; 4]; CONFIG_LEN],
+    /// The config register table as last saved to flash.
+    saved_config: [[u8; 4]; CONFIG_LEN],
+    /// The status register table.
+    status: [[u8; 4]; STATUS_LEN],
+    /// The error flags reported with every reply.
+    error_flags: ErrorFlags,
+    /// The physical parameters of the motor.
+    pub(super) model: MotorModel,
+    /// The state of the motion controller.
+    pub(super) controller: Controller,
+}
+
+impl SimMotor {
+    /// Create a motor with the given ID and the default [`MotorModel`].
+    ///
+    /// Torque is disabled and the motor is in torque mode. The gains are tuned for a stable, well-damped
+    /// response of the default model.
+    pub fn new(motor_id: u8) -> Self {
+        Self::with_model(motor_id, MotorModel::default())
+    }
+
+    /// Create a motor with the given ID and physical model.
+    pub fn with_model(motor_id: u8, model: MotorModel) -> Self {
+        let mut motor = Self {
+            config: [[0; 4]; CONFIG_LEN],
+            saved_config: [[0; 4]; CONFIG_LEN],
+            status: [[0; 4]; STATUS_LEN],
+            error_flags: ErrorFlags::empty(),
+            model,
+            controller: Controller::default(),
+        };
+        motor.set_config_u32(ConfigRegister::Id, motor_id.into());
+        motor.set_config_f32(ConfigRegister::PGainVel, 0.1);
+        motor.set_config_f32(ConfigRegister::IGainVel, 0.5);
+        motor.set_config_f32(ConfigRegister::PGainPos, 5.0);
+        motor.set_config_f32(ConfigRegister::DGainPos, 0.45);
+        motor.set_config_f32(ConfigRegister::PGainForce, 5.0);
+        motor.set_config_f32(ConfigRegister::DGainForce, 0.45);
+        motor.set_config_f32(ConfigRegister::LimitIMax, 10.0);
+        motor.set_config_f32(ConfigRegister::LimitVelMax, 50.0);
+        motor.set_config_f32(ConfigRegister::MinVoltage, 10.0);
+        motor.set_config_f32(ConfigRegister::MaxVoltage, 48.0);
+        motor.set_config_f32(ConfigRegister::TempLimitLow, 70.0);
+        motor.set_config_f32(ConfigRegister::TempLimitHigh, 90.0);
+        motor.set_status_f32(StatusRegister::InputVoltage, 24.0);
+        motor.set_status_f32(StatusRegister::WindingTemp, model.ambient_temperature);
+        motor.set_status_f32(StatusRegister::PowerstageTemp, model.ambient_temperature);
+        motor.set_status_f32(StatusRegister::IcTemp, model.ambient_temperature);
+        motor.saved_config = motor.config;
+        motor
+    }
+
+    /// The physical parameters of the motor.
+    pub fn model(&self) -> &MotorModel {
+        &self.model
+    }
+
+    /// Change the physical parameters of the motor.
+    pub fn set_model(&mut self, model: MotorModel) {
+        self.model = model;
+    }
+
+    /// The ID the motor currently answers to.
+    pub fn id(&self) -> u8 {
+        self.config_u32(ConfigRegister::Id) as u8
+    }
+
+    /// The baud rate selected by the `BaudRate` config register, if it is a supported one.
+    pub fn baud(&self) -> Option<Baud> {
+        Baud::try_from(self.config_u32(ConfigRegister::BaudRate)).ok()
+    }
+
+    /// Read a `u32` config register.
+    pub fn config_u32(&self, register: ConfigRegister) -> u32 {
+        u32::from_le_bytes(self.config[register as usize])
+    }
+
+    /// Read an `f32` config register.
+    pub fn config_f32(&self, register: ConfigRegister) -> f32 {
+        f32::from_le_bytes(self.config[register as usize])
+    }
+
+    /// Write a `u32` config register, bypassing the torque check.
+    pub fn set_config_u32(&mut self, register: ConfigRegister, value: u32) {
+        self.config[register as usize] = value.to_le_bytes();
+    }
+
+    /// Write an `f32` config register, bypassing the torque check.
+    pub fn set_config_f32(&mut self, register: ConfigRegister, value: f32) {
+        self.config[register as usize] = value.to_le_bytes();
+    }
+
+    /// Read a `u32` config register as it was last saved to flash.
+    pub fn saved_config_u32(&self, register: ConfigRegister) -> u32 {
+        u32::from_le_bytes(self.saved_config[register as usize])
+    }
+
+    /// Read an `f32` config register as it was last saved to flash.
+    pub fn saved_config_f32(&self, register: ConfigRegister) -> f32 {
+        f32::from_le_bytes(self.saved_config[register as usize])
+    }
+
+    /// Read a `u32` status register.
+    pub fn status_u32(&self, register: StatusRegister) -> u32 {
+        u32::from_le_bytes(self.status[register as usize])
+    }
+
+    /// Read an `f32` status register.
+    pub fn status_f32(&self, register: StatusRegister) -> f32 {
+        f32::from_le_bytes(self.status[register as usize])
+    }
+
+    /// Write a `u32` status register, including read-only ones.
+    pub fn set_status_u32(&mut self, register: StatusRegister, value: u32) {
+        self.status[register as usize] = value.to_le_bytes();
+    }
+
+    /// Write an `f32` status register, including read-only ones.
+    pub fn set_status_f32(&mut self, register: StatusRegister, value: f32) {
+        self.status[register as usize] = value.to_le_bytes();
+    }
+
+    /// The error flags reported with every reply.
+    pub fn error_flags(&self) -> ErrorFlags {
+        self.error_flags
+    }
+
+    /// Set the error flags reported with every reply.
+    pub fn set_error_flags(&mut self, flags: ErrorFlags) {
+        self.error_flags = flags;
+    }
+
+    /// Check if torque is enabled.
+    pub fn torque_enabled(&self) -> bool {
+        self.status_u32(StatusRegister::TorqueEnable) == 1
+    }
+
+    /// Process a single instruction addressed to this motor, returning the encoded reply.
+    pub(super) fn process(&mut self, instruction: u8, parameters: &[u8]) -> Vec<u8> {
+        let motor_id = self.id();
+        // Transient warnings that only apply to this round of communication.
+        let mut flags = ErrorFlags::empty();
+        let mut data = Vec::new();
+        match instruction {
+            PING => (),
+            READ_STAT | READ_CFG => {
+                let table = if instruction == READ_STAT {
+                    &self.status[..]
+                } else {
+                    &self.config[..]
+                };
+                for &address in parameters {
+                    match table.get(address as usize) {
+                        Some(value) => data.extend_from_slice(value),
+                        None => flags |= ErrorFlags::COMMUNICATION,
+                    }
+                }
+            },
+            WRITE_STAT | WRITE_CFG => {
+                if parameters.is_empty() || !parameters.len().is_multiple_of(5) {
+                    flags |= ErrorFlags::COMMUNICATION;
+                }
+                for chunk in parameters.chunks_exact(5) {
+                    let value = chunk[1..].try_into().unwrap();
+                    let written = if instruction == WRITE_STAT {
+                        self.write_status(chunk[0], value)
+                    } else {
+                        self.write_config(chunk[0], value)
+                    };
+                    if !written {
+                        flags |= ErrorFlags::COMMUNICATION;
+                    }
+                }
+            },
+            SAVE_CFG => self.saved_config = self.config,
+            SET_ABS_POS if parameters.len() == 8 => {
+                let position = f32::from_le_bytes(parameters[..4].try_into().unwrap());
+                let tolerance = f32::from_le_bytes(parameters[4..].try_into().unwrap());
+                self.set_absolute_position(position, tolerance);
+            },
+            _ => flags |= ErrorFlags::COMMUNICATION,
+        }
+        flags |= self.error_flags;
+        status_packet(motor_id, ERROR_BYTE_MARKER | flags.bits(), &data)
+    }
+
+    /// Process this motor's row of a bulk packet, returning the encoded reply.
+    pub(super) fn process_bulk(&mut self, read_registers: &[u8], write_registers: &[u8], row: &[u8]) -> Vec<u8> {
+        let mut flags = self.error_flags;
+        for (&address, value) in write_registers.iter().zip(row.chunks_exact(4)) {
+            if !self.write_status(address, value.try_into().unwrap()) {
+                flags |= ErrorFlags::COMMUNICATION;
+            }
+        }
+        let mut data = Vec::with_capacity(read_registers.len() * 4);
+        for &address in read_registers {
+            match self.status.get(address as usize) {
+                Some(value) => data.extend_from_slice(value),
+                None => flags |= ErrorFlags::COMMUNICATION,
+            }
+        }
+        status_packet(self.id(), ERROR_BYTE_MARKER | flags.bits(), &data)
+    }
+
+    /// Write a status register from the bus. Read-only registers can not be written.
+    fn write_status(&mut self, address: u8, value: [u8; 4]) -> bool {
+        if address >= FIRST_READ_ONLY_STATUS {
+            return false;
+        }
+        self.status[address as usize] = value;
+        if address == StatusRegister::TorqueEnable as u8 {
+            // Writing 0x03 triggers an ESTOP, which is cleared by disabling torque.
+            match TorqueState::try_from(u32::from_le_bytes(value)) {
+                Ok(TorqueState::Estop) => self.error_flags |= ErrorFlags::WATCHDOG_ESTOP,
+                Ok(TorqueState::Disabled) => self.error_flags -= ErrorFlags::WATCHDOG_ESTOP,
+                _ => (),
+            }
+        }
+        true
+    }
+
+    /// Write a config register from the bus. Config registers can only be written while torque is disabled.
+    fn write_config(&mut self, address: u8, value: [u8; 4]) -> bool {
+        let torque_enabled = self.torque_enabled();
+        let Some(register) = self.config.get_mut(address as usize) else {
+            return false;
+        };
+        if torque_enabled {
+            return false;
+        }
+        *register = value;
+        true
+    }
+
+    /// Re-home the motor so that its present position matches `position`, then save the config.
+    fn set_absolute_position(&mut self, position: f32, tolerance: f32) {
+        let present = self.status_f32(StatusRegister::PresentPos);
+        let mut offset = present - position;
+        if !offset.is_finite() {
+            self.error_flags |= ErrorFlags::ABSOLUTE_POSITION;
+            return;
+        }
+        if tolerance != 0.0 {
+            // Pick the multi-turn value closest to the expected position.
+            // `f32::rem_euclid` needs `std`, so wrap with `%` and shift negative remainders up by a turn.
+            let wrapped = (offset + PI) % TAU;
+            offset = if wrapped < 0.0 { wrapped + TAU } else { wrapped } - PI;
+            if offset.abs() > tolerance {
+                self.error_flags |= ErrorFlags::ABSOLUTE_POSITION;
+                return;
+            }
+        }
+        self.error_flags.remove(ErrorFlags::ABSOLUTE_POSITION);
+        let homing_offset = self.config_f32(ConfigRegister::HomingOffset);
+        self.set_config_f32(ConfigRegister::HomingOffset, homing_offset + offset);
+        self.set_status_f32(StatusRegister::PresentPos, present - offset);
+        self.saved_config = self.config;
+    }
+}
@@ -3,6 +3,9 @@
 //! These assert the exact request bytes produced (cross-checked against the firmware packet layout)
 //! and that per-motor responses are parsed and dispatched correctly.
 
+// Kept as written before clippy flagged them.
+#![allow(clippy::type_complexity, clippy::needless_borrows_for_generic_args)]
+
 use std::time::Duration;
 
 use ww_bear::error::{InvalidMessage, ReadError};
@@ -309,3 +312,99 @@ fn response_decode_helpers_unpack_le_bytes() {
     assert_eq!(u.u32(0), Some(0x0102_0304));
     assert_eq!(u.u32(1), None);
 }
+
+#[test]
+fn bulk_plan_matches_bulk_write() {
+    let writes = [StatusRegister::GoalPos];
+    let cycles = [
+        [[0xAAu8, 0xBB, 0xCC, 0xDD], [0x11, 0x22, 0x33, 0x44]],
+        [[1, 2, 3, 4], [5, 6, 7, 8]],
+    ];
+
+    let mut expected_bus = open(Vec::new());
+    let mut bus = open(Vec::new());
+    let plan = bus.bulk_plan(&[1, 2], &[], &writes).unwrap();
+    assert_eq!(plan.motor_ids().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(plan.row_len(), 4);
+
+    for rows in cycles {
+        let devices = [
+            BulkWriteData {
+                motor_id: 1,
+                data: rows[0],
+            },
+            BulkWriteData {
+                motor_id: 2,
+                data: rows[1],
+            },
+        ];
+        expected_bus.bulk_write(devices, &writes).unwrap();
+        bus.bulk_execute(&plan, rows, |_| {}).unwrap();
+
+        // Another transfer overwrites the write buffer, so the next cycle has to restore the plan's packet.
+        let other = [BulkWriteData::from_u32(3, 0)];
+        expected_bus.bulk_write(other, &[StatusRegister::GoalIq]).unwrap();
+        bus.bulk_write(other, &[StatusRegister::GoalIq]).unwrap();
+    }
+    assert_eq!(bus.serial_port().written, expected_bus.serial_port().written);
+}
+
+#[test]
+fn bulk_plans_from_other_buses_are_restamped() {
+    let writes = [StatusRegister::GoalPos];
+    let mut bus = open(Vec::new());
+    let mut other_bus = open(Vec::new());
+    let plan = bus.bulk_plan(&[1], &[], &writes).unwrap();
+    let other_plan = other_bus.bulk_plan(&[2], &[], &writes).unwrap();
+
+    bus.bulk_execute(&plan, [[1u8, 2, 3, 4]], |_| {}).unwrap();
+    bus.serial_port().written.clear();
+    bus.bulk_execute(&other_plan, [[5u8, 6, 7, 8]], |_| {}).unwrap();
+
+    let mut expected_bus = open(Vec::new());
+    let devices = [BulkWriteData {
+        motor_id: 2,
+        data: [5u8, 6, 7, 8],
+    }];
+    expected_bus.bulk_write(devices, &writes).unwrap();
+    assert_eq!(bus.serial_port().written, expected_bus.serial_port().written);
+}
+
+#[test]
+fn bulk_plan_reads_replies() {
+    let mut responses = status_packet(1, 0x80, &[1, 2, 3, 4]);
+    responses.extend_from_slice(&status_packet(2, 0x80, &[5, 6, 7, 8]));
+    let mut bus = open(responses);
+    let plan = bus
+        .bulk_plan(&[1, 2], &[StatusRegister::PresentPos], &[StatusRegister::GoalPos])
+        .unwrap();
+    let replies = bus
+        .bulk_execute_alloc(&plan, [[0xAAu8, 0xBB, 0xCC, 0xDD], [0x11, 0x22, 0x33, 0x44]])
+        .unwrap();
+
+    // The same packet as `bulk_read_write_request`.
+    let expected = [
+        0xFF, 0xFF, 0xFE, 0x10, 0x12, 0x02, 0x11, 0x09, 0x05, 0x01, 0xAA, 0xBB, 0xCC, 0xDD, 0x02, 0x11, 0x22, 0x33,
+        0x44, 0x03,
+    ];
+    assert_eq!(bus.serial_port().written, expected);
+    assert_eq!(replies.len(), 2);
+    assert_eq!(replies[0].as_ref().unwrap().data, [1, 2, 3, 4]);
+    assert_eq!(replies[1].as_ref().unwrap().motor_id, 2);
+}
+
+#[test]
+fn bulk_plan_rejects_wrong_row_count() {
+    use ww_bear::error::{TransferError, WriteError};
+
+    let mut bus = open(Vec::new());
+    let plan = bus.bulk_plan(&[1, 2], &[], &[StatusRegister::GoalPos]).unwrap();
+    match bus.bulk_execute(&plan, [[0u8; 4]], |_| {}) {
+        Err(TransferError::WriteError(WriteError::DeviceCount(e))) => {
+            assert_eq!(e.expected, 2);
+            assert_eq!(e.actual, 1);
+        },
+        other => panic!("expected DeviceCount error, got {other:?}"),
+    }
+    assert!(bus.serial_port().written.is_empty());
+}
@@ -0,0 +1,133 @@
+//! Tests for driving several buses as one, using the simulated bus from `ww_bear::sim`.
+
+use std::time::Duration;
+
+use ww_bear::StatusRegister;
+use ww_bear::asynchronous::{Bus, BusSet, SerialPort};
+use ww_bear::error::{AddJointError, BusSetError};
+use ww_bear::registers::status::{GoalPos, GoalVel};
+use ww_bear::sim::{SimBus, SimError};
+
+/// Two legs on their own bus, with the same motor IDs on each.
+fn legs() -> BusSet<SimBus> {
+    let buses = [&[1, 2], &[1, 2]].map(|ids| Bus::new(SimBus::with_motors(ids)).unwrap());
+    let mut set = BusSet::new(buses);
+    set.add_joint("left_hip", 0, 1).unwrap();
+    set.add_joint("right_hip", 1, 1).unwrap();
+    set.add_joint("left_knee", 0, 2).unwrap();
+    set.add_joint("right_knee", 1, 2).unwrap();
+    set
+}
+
+#[test]
+fn joints_map_to_their_bus() {
+    let mut set = legs();
+    assert_eq!(set.joint_index("right_hip"), Some(1));
+    assert_eq!(set.joint_location(1), Some((1, 1)));
+    assert_eq!(
+        set.add_joint("left_hip", 1, 3),
+        Err(AddJointError::DuplicateName { joint: 0 })
+    );
+    assert_eq!(
+        set.add_joint("tail", 0, 2),
+        Err(AddJointError::DuplicateMotor { joint: 2 })
+    );
+    assert_eq!(set.add_joint("tail", 2, 1), Err(AddJointError::UnknownBus { bus: 2 }));
+    assert_eq!(set.joints().len(), 4);
+}
+
+#[tokio::test]
+async fn bulk_transfers_are_merged_per_joint() {
+    let mut set = legs();
+    set.bulk_write_typed::<(GoalPos, GoalVel)>([(0.5, 1.0), (1.5, 2.0), (2.5, 3.0), (3.5, 4.0)])
+        .await
+        .unwrap();
+    let goals = set.bulk_read_typed::<(GoalPos, GoalVel)>().await.unwrap();
+    let goals: Vec<_> = goals.into_iter().map(|reply| reply.unwrap().data).collect();
+    assert_eq!(goals, [(0.5, 1.0), (1.5, 2.0), (2.5, 3.0), (3.5, 4.0)]);
+
+    // The right knee is motor 2 on the second bus.
+    assert_eq!(set.bus(1).unwrap().read_goal_pos(2).await.unwrap().data, 3.5);
+    let replies = set.bulk_read(&[StatusRegister::GoalVel]).await.unwrap();
+    assert_eq!(replies[3].as_ref().unwrap().data, 4.0f32.to_le_bytes());
+}
+
+#[tokio::test]
+async fn a_missing_motor_only_fails_its_joint() {
+    let mut set = legs();
+    set.bus(1).unwrap().serial_port().remove_motor(2);
+    let replies = set.bulk_read(&[StatusRegister::GoalPos]).await.unwrap();
+    assert_eq!(replies.len(), 4);
+    assert!(replies[..3].iter().all(Result::is_ok));
+    assert!(replies[3].is_err());
+
+    match set.bulk_write(&[[0u8; 4]], &[StatusRegister::GoalPos]).await {
+        Err(BusSetError::DeviceCount(e)) => {
+            assert_eq!(e.expected, 4);
+            assert_eq!(e.actual, 1);
+        },
+        other => panic!("expected a DeviceCount error, got {other:?}"),
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn buses_transfer_concurrently() {
+    let latency = Duration::from_millis(10);
+    let buses = [1, 2].map(|motor_id| {
+        let port = SlowPort {
+            sim: SimBus::with_motors(&[motor_id]),
+            latency,
+        };
+        Bus::new(port).unwrap()
+    });
+    let mut set = BusSet::new(buses);
+    set.add_joint("left", 0, 1).unwrap();
+    set.add_joint("right", 1, 2).unwrap();
+
+    let start = tokio::time::Instant::now();
+    let replies = set.bulk_read(&[StatusRegister::GoalPos]).await.unwrap();
+    assert!(replies.iter().all(Result::is_ok));
+    // One bus after the other would take twice the latency.
+    assert_eq!(start.elapsed(), latency);
+}
+
+/// A simulated bus that takes `latency` of (tokio) time to send each packet.
+struct SlowPort {
+    sim: SimBus,
+    latency: Duration,
+}
+
+impl SerialPort for SlowPort {
+    type Error = SimError;
+
+    type Instant = Duration;
+
+    fn baud_rate(&self) -> Result<u32, Self::Error> {
+        SerialPort::baud_rate(&self.sim)
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Self::Error> {
+        SerialPort::set_baud_rate(&mut self.sim, baud_rate)
+    }
+
+    fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+        SerialPort::discard_input_buffer(&mut self.sim)
+    }
+
+    async fn read(&mut self, buffer: &mut [u8], deadline: &Self::Instant) -> Result<usize, Self::Error> {
+        SerialPort::read(&mut self.sim, buffer, deadline).await
+    }
+
+    async fn write_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
+        tokio::time::sleep(self.latency).await;
+        SerialPort::write_all(&mut self.sim, buffer).await
+    }
+
+    fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+        SerialPort::make_deadline(&self.sim, timeout)
+    }
+
+    fn is_timeout_error(error: &Self::Error) -> bool {
+        <SimBus as SerialPort>::is_timeout_error(error)
+    }
+}
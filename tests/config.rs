@@ -0,0 +1,138 @@
+//! Tests for reading and applying a full motor config, using the simulated bus from `ww_bear::sim`.
+
+use std::time::Duration;
+
+use ww_bear::error::ApplyConfigError;
+use ww_bear::sim::{Fault, FaultInjector, SimBus};
+use ww_bear::{Baud, Bus, ConfigRegister, OperatingMode, RegisterValue, SerialPort};
+
+#[test]
+fn read_all_config_reads_every_register() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1])).unwrap();
+    bus.write_p_gain_pos(1, 4.5).unwrap();
+    bus.write_mode(1, OperatingMode::Velocity).unwrap();
+    let config = bus.read_all_config(1).unwrap();
+    assert_eq!(config.id, 1);
+    assert_eq!(config.mode, OperatingMode::Velocity);
+    assert_eq!(config.p_gain_pos, 4.5);
+    assert_eq!(config.get(ConfigRegister::PGainPos), RegisterValue::F32(4.5));
+    assert_eq!(config.limit_i_max, bus.read_limit_i_max(1).unwrap().data);
+}
+
+#[test]
+fn apply_config_writes_the_changed_registers() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1, 2])).unwrap();
+    let mut config = bus.read_all_config(1).unwrap();
+    assert_eq!(bus.apply_config(1, &config, true).unwrap(), 0);
+    // The config of motor 1 would move motor 2 onto its ID.
+    assert!(matches!(
+        bus.apply_config(2, &config, true),
+        Err(ApplyConfigError::IdInUse { motor_id: 1 })
+    ));
+
+    config.id = 2;
+    config.p_gain_pos = 7.0;
+    config.limit_pos_max = 1.5;
+    assert_eq!(bus.apply_config(2, &config, true).unwrap(), 2);
+    assert_eq!(bus.read_all_config(2).unwrap(), config);
+    let motor = bus.serial_port().motor(2).unwrap();
+    assert_eq!(motor.saved_config_f32(ConfigRegister::PGainPos), 7.0);
+    assert_eq!(motor.saved_config_f32(ConfigRegister::LimitPosMax), 1.5);
+}
+
+#[test]
+fn apply_config_moves_the_motor_last() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1])).unwrap();
+    let mut config = bus.read_all_config(1).unwrap();
+    config.id = 5;
+    config.baud_rate = Baud::B2000000;
+    config.d_gain_vel = 0.25;
+    assert_eq!(bus.apply_config(1, &config, false).unwrap(), 3);
+    assert_eq!(bus.serial_port().baud_rate().unwrap(), 2_000_000);
+    assert_eq!(bus.read_d_gain_vel(5).unwrap().data, 0.25);
+    let motor = bus.serial_port().motor(5).unwrap();
+    assert_eq!(motor.saved_config_u32(ConfigRegister::Id), 1);
+}
+
+#[test]
+fn apply_config_restores_the_old_baud_rate_on_failure() {
+    let mut bus = Bus::new(FaultInjector::new(SimBus::with_motors(&[1]))).unwrap();
+    let mut config = bus.read_all_config(1).unwrap();
+    config.baud_rate = Baud::B2000000;
+    // The torque, config and baud rate replies pass, and the acknowledgement of the save is corrupted.
+    for _ in 0..3 {
+        bus.serial_port().inject(Fault::Pass);
+    }
+    bus.serial_port().inject(Fault::CorruptChecksum);
+    assert!(matches!(
+        bus.apply_config(1, &config, true),
+        Err(ApplyConfigError::Save(_))
+    ));
+    assert_eq!(bus.serial_port().baud_rate().unwrap(), 8_000_000);
+}
+
+#[test]
+fn apply_config_requires_torque_to_be_disabled() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1])).unwrap();
+    let mut config = bus.read_all_config(1).unwrap();
+    config.p_gain_pos = 9.0;
+    bus.enable_torque(1).unwrap();
+    assert!(matches!(
+        bus.apply_config(1, &config, false),
+        Err(ApplyConfigError::TorqueEnabled { motor_id: 1 })
+    ));
+    config.id = 0xFE;
+    assert!(matches!(
+        bus.apply_config(1, &config, false),
+        Err(ApplyConfigError::InvalidId { id: 0xFE })
+    ));
+    assert_ne!(bus.read_p_gain_pos(1).unwrap().data, 9.0);
+}
+
+#[test]
+fn bulk_read_config_reads_each_motor() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1, 2])).unwrap();
+    bus.write_p_gain_pos(2, 3.0).unwrap();
+    let configs = bus.bulk_read_config_alloc(&[1, 9, 2]).unwrap();
+    assert_eq!(configs.len(), 3);
+    assert_eq!(configs[0].as_ref().unwrap().data, bus.read_all_config(1).unwrap());
+    assert!(configs[1].is_err());
+    let config = &configs[2].as_ref().unwrap().data;
+    assert_eq!(config.id, 2);
+    assert_eq!(config.p_gain_pos, 3.0);
+}
+
+#[test]
+fn bulk_read_config_pipelines_the_requests() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1, 2, 3, 4])).unwrap();
+    let latency = Duration::from_millis(1);
+    bus.serial_port().set_response_latency(latency);
+    let start = bus.serial_port().now();
+    let configs = bus.bulk_read_config_alloc(&[1, 2, 3, 4]).unwrap();
+    assert!(configs.iter().all(Result::is_ok));
+    // One at a time, the motors would take at least four times the latency to reply.
+    assert!(bus.serial_port().now() - start < 2 * latency);
+}
+
+#[test]
+fn bulk_read_config_drops_stray_replies() {
+    let mut bus = Bus::new(FaultInjector::new(SimBus::with_motors(&[1, 2, 3]))).unwrap();
+    // A ping reply from motor 9 arrives before the first config.
+    let stray = vec![0xFF, 0xFF, 0x09, 0x02, 0x00, 0xF4];
+    bus.serial_port().inject(Fault::LeadingGarbage(stray));
+    let configs = bus.bulk_read_config_alloc(&[1, 2, 3]).unwrap();
+    let ids: Vec<_> = configs.iter().map(|config| config.as_ref().unwrap().data.id).collect();
+    assert_eq!(ids, [1, 2, 3]);
+}
+
+#[test]
+fn small_read_buffers_read_the_config_in_chunks() {
+    let mut bus = Bus::with_buffers(SimBus::with_motors(&[1, 2]), vec![0; 40], vec![0; 128]).unwrap();
+    bus.write_limit_pos_max(2, 2.5).unwrap();
+    let config = bus.read_all_config(2).unwrap();
+    assert_eq!(config.id, 2);
+    assert_eq!(config.limit_pos_max, 2.5);
+    let configs = bus.bulk_read_config_alloc(&[1, 2]).unwrap();
+    assert_eq!(configs[0].as_ref().unwrap().data.id, 1);
+    assert_eq!(configs[1].as_ref().unwrap().data, config);
+}
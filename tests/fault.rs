@@ -0,0 +1,200 @@
+//! Tests for the host's handling of a bad bus, using the fault injector from `ww_bear::sim`.
+
+use std::time::Duration;
+
+use ww_bear::error::{InvalidMessage, ReadError, TransferError};
+use ww_bear::sim::{Fault, FaultInjector, SimBus, SimError};
+use ww_bear::{Bus, ErrorFlags, ErrorPolicy, RetryOn, RetryPolicy, StatusRegister};
+
+fn open(motor_ids: &[u8]) -> Bus<FaultInjector<SimBus>, Vec<u8>> {
+    let mut sim = SimBus::with_motors(motor_ids);
+    for &id in motor_ids {
+        sim.motor_mut(id)
+            .unwrap()
+            .set_status_f32(StatusRegister::PresentPos, f32::from(id));
+    }
+    Bus::new(FaultInjector::new(sim)).unwrap()
+}
+
+#[test]
+fn corrupt_checksum_is_reported() {
+    let mut bus = open(&[1]);
+    bus.serial_port().inject(Fault::CorruptChecksum);
+    match bus.read_present_pos(1) {
+        Err(TransferError::ReadError(ReadError::InvalidMessage(InvalidMessage::InvalidChecksum(_)))) => (),
+        other => panic!("expected an invalid checksum, got {other:?}"),
+    }
+    assert_eq!(bus.read_present_pos(1).unwrap().data, 1.0);
+}
+
+#[test]
+fn wrong_reply_id_is_reported() {
+    let mut bus = open(&[1]);
+    bus.serial_port().inject(Fault::ChangeId(7));
+    match bus.read_present_pos(1) {
+        Err(TransferError::ReadError(ReadError::InvalidMessage(InvalidMessage::InvalidPacketId(e)))) => {
+            assert_eq!(e.actual, 7);
+            assert_eq!(e.expected, Some(1));
+        },
+        other => panic!("expected an invalid packet id, got {other:?}"),
+    }
+}
+
+#[test]
+fn garbage_and_split_reads_are_tolerated() {
+    let mut bus = open(&[1]);
+    bus.serial_port()
+        .inject(Fault::LeadingGarbage(vec![0x00, 0xFF, 0x13, 0x37]));
+    assert_eq!(bus.read_present_pos(1).unwrap().data, 1.0);
+    bus.serial_port().inject(Fault::SplitReads(1));
+    assert_eq!(bus.read_present_pos(1).unwrap().data, 1.0);
+}
+
+#[test]
+fn delayed_and_truncated_replies_time_out() {
+    let mut bus = open(&[1]);
+    bus.serial_port().inject(Fault::Delay);
+    assert!(matches!(
+        bus.read_present_pos(1),
+        Err(TransferError::ReadError(ReadError::Io(SimError::Timeout)))
+    ));
+    bus.serial_port().inject(Fault::DropBytes(2));
+    assert!(matches!(
+        bus.read_present_pos(1),
+        Err(TransferError::ReadError(ReadError::Io(SimError::Timeout)))
+    ));
+    // The late reply is discarded before the next instruction is sent.
+    assert_eq!(bus.read_present_pos(1).unwrap().data, 1.0);
+}
+
+#[test]
+fn error_flags_reach_the_response() {
+    let mut bus = open(&[1]);
+    bus.serial_port().inject(Fault::SetErrorFlags(ErrorFlags::OVERHEAT));
+    assert_eq!(bus.read_present_pos(1).unwrap().warning, ErrorFlags::OVERHEAT);
+}
+
+#[test]
+fn bulk_fault_only_affects_its_slot() {
+    let mut bus = open(&[1, 2, 3]);
+    bus.serial_port().inject(Fault::Pass);
+    bus.serial_port().inject(Fault::CorruptChecksum);
+    let replies = bus.bulk_read_alloc(&[1, 2, 3], &[StatusRegister::PresentPos]).unwrap();
+    assert_eq!(replies[0].as_ref().unwrap().f32(0), Some(1.0));
+    assert!(matches!(
+        replies[1],
+        Err(ReadError::InvalidMessage(InvalidMessage::InvalidChecksum(_)))
+    ));
+    assert_eq!(replies[2].as_ref().unwrap().f32(0), Some(3.0));
+}
+
+#[test]
+fn error_flags_are_rejected_by_default() {
+    let mut bus = open(&[1]);
+    bus.serial_port()
+        .inject(Fault::SetErrorFlags(ErrorFlags::WATCHDOG_ESTOP));
+    match bus.read_present_pos(1) {
+        Err(TransferError::ReadError(ReadError::MotorError(e))) => {
+            assert_eq!(e.motor_id, 1);
+            assert_eq!(e.flags, ErrorFlags::WATCHDOG_ESTOP);
+        },
+        other => panic!("expected a motor error, got {other:?}"),
+    }
+}
+
+#[test]
+fn write_acknowledgements_are_checked() {
+    let mut bus = open(&[1]);
+    bus.serial_port().inject(Fault::ChangeId(2));
+    assert!(matches!(
+        bus.write_goal_pos(1, 1.0),
+        Err(TransferError::ReadError(ReadError::InvalidMessage(
+            InvalidMessage::InvalidPacketId(_)
+        )))
+    ));
+    bus.serial_port()
+        .inject(Fault::SetErrorFlags(ErrorFlags::WATCHDOG_ESTOP));
+    assert!(matches!(
+        bus.save_config(1),
+        Err(TransferError::ReadError(ReadError::MotorError(_)))
+    ));
+    bus.serial_port().inject(Fault::Delay);
+    assert!(matches!(
+        bus.set_absolute_position(1, 0.0, 0.0),
+        Err(TransferError::ReadError(ReadError::Io(SimError::Timeout)))
+    ));
+}
+
+#[test]
+fn error_policy_can_accept_error_flags() {
+    let mut bus = open(&[1]);
+    bus.serial_port()
+        .inner_mut()
+        .motor_mut(1)
+        .unwrap()
+        .set_error_flags(ErrorFlags::HARDWARE);
+    assert!(bus.ping(1).is_err());
+
+    bus.set_error_policy(ErrorPolicy::WarnOnly);
+    assert_eq!(bus.read_present_pos(1).unwrap().warning, ErrorFlags::HARDWARE);
+    bus.set_error_policy(ErrorPolicy::Ignore);
+    assert_eq!(bus.ping(1).unwrap().warning, ErrorFlags::HARDWARE);
+}
+
+#[test]
+fn bulk_motor_error_only_affects_its_slot() {
+    let mut bus = open(&[1, 2]);
+    bus.serial_port().inject(Fault::SetErrorFlags(ErrorFlags::JOINT_LIMIT));
+    let replies = bus.bulk_read_alloc(&[1, 2], &[StatusRegister::PresentPos]).unwrap();
+    assert!(matches!(&replies[0], Err(ReadError::MotorError(e)) if e.motor_id == 1));
+    assert_eq!(replies[1].as_ref().unwrap().f32(0), Some(2.0));
+}
+
+#[test]
+fn retry_policy_recovers_from_transient_faults() {
+    let mut bus = open(&[1]);
+    bus.set_retry_policy(RetryPolicy::attempts(3));
+    bus.serial_port().inject(Fault::CorruptChecksum);
+    bus.serial_port().inject(Fault::Delay);
+    assert_eq!(bus.read_present_pos(1).unwrap().data, 1.0);
+    assert_eq!(bus.serial_port().pending_faults(), 0);
+
+    bus.serial_port().inject(Fault::ChangeId(2));
+    bus.write_goal_pos(1, 0.5).unwrap();
+    bus.serial_port().inject(Fault::CorruptChecksum);
+    bus.ping(1).unwrap();
+}
+
+#[test]
+fn retry_policy_gives_up() {
+    let mut bus = open(&[1]);
+    bus.set_retry_policy(RetryPolicy::attempts(2));
+    for _ in 0..3 {
+        bus.serial_port().inject(Fault::CorruptChecksum);
+    }
+    assert!(matches!(
+        bus.read_present_pos(1),
+        Err(TransferError::ReadError(ReadError::InvalidMessage(
+            InvalidMessage::InvalidChecksum(_)
+        )))
+    ));
+    assert_eq!(bus.serial_port().pending_faults(), 1);
+    bus.serial_port().clear_faults();
+
+    // Failures that are not selected are not retried.
+    bus.set_retry_policy(RetryPolicy::attempts(2).with_retry_on(RetryOn::TIMEOUT));
+    bus.serial_port().inject(Fault::CorruptChecksum);
+    assert!(bus.read_present_pos(1).is_err());
+    bus.serial_port().inject(Fault::SetErrorFlags(ErrorFlags::HARDWARE));
+    assert!(bus.read_present_pos(1).is_err());
+}
+
+#[test]
+fn retry_policy_waits_for_the_backoff() {
+    let mut bus = open(&[1]);
+    bus.set_retry_policy(RetryPolicy::attempts(2).with_backoff(Duration::from_millis(20)));
+    bus.serial_port().inject(Fault::CorruptChecksum);
+    let start = bus.serial_port().inner().now();
+    bus.read_present_pos(1).unwrap();
+    assert!(bus.serial_port().inner().now() - start >= Duration::from_millis(20));
+}
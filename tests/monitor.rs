@@ -0,0 +1,125 @@
+//! Tests for the telemetry monitor, using the simulated bus from `ww_bear::sim`.
+
+use std::time::Duration;
+
+use ww_bear::error::MonitorError;
+use ww_bear::monitor::{CsvSink, JsonLinesSink, MonitorConfig, Sample};
+use ww_bear::sim::{Fault, FaultInjector, SimBus};
+use ww_bear::{Bus, ErrorFlags, RegisterValue, StatusRegister};
+
+fn open(motor_ids: &[u8]) -> Bus<FaultInjector<SimBus>, Vec<u8>> {
+    let mut sim = SimBus::with_motors(motor_ids);
+    for &id in motor_ids {
+        sim.motor_mut(id)
+            .unwrap()
+            .set_status_f32(StatusRegister::PresentPos, f32::from(id));
+    }
+    Bus::new(FaultInjector::new(sim)).unwrap()
+}
+
+#[test]
+fn samples_are_timestamped_at_the_target_rate() {
+    let mut bus = open(&[1, 2]);
+    let registers = [StatusRegister::PresentPos, StatusRegister::TorqueEnable];
+    let config = MonitorConfig::new(&[1, 2], &registers, 100.0).unwrap().with_cycles(3);
+    let mut samples = Vec::new();
+    bus.monitor(&config, &mut |sample: &Sample| {
+        let reading = sample.reply.unwrap();
+        samples.push((sample.cycle, sample.timestamp, sample.motor_id, reading.values.to_vec()));
+        Ok::<_, ()>(())
+    })
+    .unwrap();
+
+    assert_eq!(samples.len(), 6);
+    for (i, (cycle, timestamp, motor_id, values)) in samples.into_iter().enumerate() {
+        assert_eq!(cycle, i as u64 / 2);
+        assert_eq!(timestamp, Duration::from_millis(10) * cycle as u32);
+        assert_eq!(motor_id, [1, 2][i % 2]);
+        assert_eq!(values, [RegisterValue::F32(f32::from(motor_id)), RegisterValue::U32(0)]);
+    }
+}
+
+#[test]
+fn late_cycles_are_not_caught_up() {
+    let mut bus = open(&[1]);
+    // The reply of the first cycle times out, which stalls the monitor for several periods.
+    bus.serial_port().inject(Fault::Delay);
+    let config = MonitorConfig::new(&[1], &[StatusRegister::PresentPos], 1000.0)
+        .unwrap()
+        .with_cycles(3);
+    let mut timestamps = Vec::new();
+    bus.monitor(&config, &mut |sample: &Sample| {
+        timestamps.push(sample.timestamp);
+        Ok::<_, ()>(())
+    })
+    .unwrap();
+
+    assert!(timestamps[1] > 2 * config.period);
+    assert_eq!(timestamps[2] - timestamps[1], config.period);
+}
+
+#[test]
+fn csv_sink_records_dropped_replies() {
+    let mut bus = open(&[1, 2]);
+    bus.serial_port().inject(Fault::SetErrorFlags(ErrorFlags::OVERHEAT));
+    bus.serial_port().inject(Fault::CorruptChecksum);
+    let registers = [StatusRegister::PresentPos];
+    let mut sink = CsvSink::new(Vec::new());
+    let config = MonitorConfig::new(&[1, 2], &registers, 1000.0).unwrap().with_cycles(2);
+    bus.monitor(&config, &mut sink).unwrap();
+
+    let csv = String::from_utf8(sink.into_inner()).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines[0], "cycle,timestamp,motor_id,warning,error,PresentPos");
+    assert_eq!(lines[1], "0,0.000000,1,OVERHEAT,,1");
+    assert!(lines[2].starts_with("0,0.000000,2,,\"invalid checksum"));
+    assert!(lines[2].ends_with("\","));
+    assert_eq!(lines[3], "1,0.001000,1,,,1");
+    assert_eq!(lines[4], "1,0.001000,2,,,2");
+}
+
+#[test]
+fn json_lines_sink_writes_one_object_per_sample() {
+    let mut bus = open(&[1, 2]);
+    bus.serial_port().inject(Fault::Pass);
+    bus.serial_port().inject(Fault::Delay);
+    let registers = [StatusRegister::PresentPos, StatusRegister::PresentVel];
+    let mut sink = JsonLinesSink::new(Vec::new());
+    let config = MonitorConfig::new(&[1, 2], &registers, 100.0).unwrap().with_cycles(1);
+    bus.monitor(&config, &mut sink).unwrap();
+
+    let json = String::from_utf8(sink.into_inner()).unwrap();
+    let lines: Vec<_> = json.lines().collect();
+    assert_eq!(
+        lines[0],
+        r#"{"cycle":0,"timestamp":0.000000,"motor_id":1,"warning":[],"values":{"PresentPos":1,"PresentVel":0}}"#
+    );
+    assert_eq!(
+        lines[1],
+        r#"{"cycle":0,"timestamp":0.000000,"motor_id":2,"error":"timed out waiting for a reply"}"#
+    );
+}
+
+#[test]
+fn rates_must_be_positive() {
+    let registers = [StatusRegister::PresentPos];
+    for rate in [0.0, -1.0, f32::NAN, f32::MIN_POSITIVE] {
+        assert!(MonitorConfig::new(&[1], &registers, rate).is_none());
+    }
+    let config = MonitorConfig::new(&[1], &registers, 4.0).unwrap();
+    assert_eq!(config.period, Duration::from_millis(250));
+}
+
+#[test]
+fn sink_errors_stop_the_monitor() {
+    let mut bus = open(&[1]);
+    let registers = [StatusRegister::PresentPos];
+    let mut count = 0;
+    let config = MonitorConfig::new(&[1], &registers, 100.0).unwrap();
+    let result = bus.monitor(&config, &mut |sample: &Sample| {
+        count += 1;
+        if sample.cycle == 4 { Err("full") } else { Ok(()) }
+    });
+    assert!(matches!(result, Err(MonitorError::Sink("full"))));
+    assert_eq!(count, 5);
+}
@@ -0,0 +1,127 @@
+//! Tests for changing the ID and baud rate of a motor, using the simulated bus from `ww_bear::sim`.
+
+use ww_bear::error::{ChangeBaudRateError, ChangeIdError, ReadError, TransferError};
+use ww_bear::sim::{Fault, FaultInjector, SimBus, SimError};
+use ww_bear::{Baud, Bus, ConfigRegister, ErrorFlags, SerialPort, StatusRegister};
+
+#[test]
+fn change_id_moves_the_motor() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1])).unwrap();
+    bus.change_id(1, 9).unwrap();
+    assert!(bus.ping(1).is_err());
+    assert_eq!(bus.ping(9).unwrap().motor_id, 9);
+    let motor = bus.serial_port().motor(9).unwrap();
+    assert_eq!(motor.saved_config_u32(ConfigRegister::Id), 9);
+}
+
+#[test]
+fn change_id_rejects_a_used_id() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1, 2])).unwrap();
+    assert!(matches!(
+        bus.change_id(1, 2),
+        Err(ChangeIdError::IdInUse { motor_id: 2 })
+    ));
+    assert!(matches!(
+        bus.change_id(1, 0xFE),
+        Err(ChangeIdError::InvalidId { motor_id: 0xFE })
+    ));
+    assert_eq!(bus.ping(1).unwrap().motor_id, 1);
+}
+
+#[test]
+fn change_id_rejects_the_broadcast_id() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1, 2])).unwrap();
+    assert!(matches!(
+        bus.change_id(0xFE, 5),
+        Err(ChangeIdError::InvalidId { motor_id: 0xFE })
+    ));
+    assert_eq!(bus.ping(1).unwrap().motor_id, 1);
+    assert_eq!(bus.ping(2).unwrap().motor_id, 2);
+    assert!(bus.ping(5).is_err());
+}
+
+#[test]
+fn change_id_reports_the_failed_step() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1])).unwrap();
+    assert!(matches!(
+        bus.change_id(3, 4),
+        Err(ChangeIdError::Write(TransferError::ReadError(ReadError::Io(
+            SimError::Timeout
+        ))))
+    ));
+
+    // Config writes are rejected while torque is enabled.
+    bus.enable_torque(1).unwrap();
+    assert!(matches!(
+        bus.change_id(1, 4),
+        Err(ChangeIdError::Rejected { motor_id: 1 })
+    ));
+    assert_eq!(bus.ping(1).unwrap().motor_id, 1);
+}
+
+#[test]
+fn stale_warnings_do_not_fail_a_change() {
+    let mut bus = Bus::new(FaultInjector::new(SimBus::with_motors(&[1]))).unwrap();
+    // A communication warning left over from an earlier packet, on the acknowledgement of the write.
+    let stale = Fault::SetErrorFlags(ErrorFlags::COMMUNICATION);
+    bus.serial_port().inject(stale.clone());
+    bus.change_id(1, 4).unwrap();
+    bus.serial_port().inject(stale);
+    bus.change_baud_rate(4, Baud::B1000000).unwrap();
+    assert_eq!(bus.read_baud_rate(4).unwrap().data, Baud::B1000000);
+}
+
+#[test]
+fn change_baud_rate_switches_the_motor_and_the_host() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1])).unwrap();
+    bus.change_baud_rate(1, Baud::B1000000).unwrap();
+    assert_eq!(bus.serial_port().baud_rate().unwrap(), 1_000_000);
+    assert_eq!(bus.read_baud_rate(1).unwrap().data, Baud::B1000000);
+    let motor = bus.serial_port().motor(1).unwrap();
+    assert_eq!(motor.saved_config_u32(ConfigRegister::BaudRate), 4);
+    assert_eq!(motor.baud(), Some(Baud::B1000000));
+    assert_eq!(motor.status_u32(StatusRegister::TorqueEnable), 0);
+}
+
+#[test]
+fn change_baud_rate_rejects_the_broadcast_id() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1, 2])).unwrap();
+    assert!(matches!(
+        bus.change_baud_rate(0xFE, Baud::B1000000),
+        Err(ChangeBaudRateError::InvalidId { motor_id: 0xFE })
+    ));
+    assert_eq!(bus.serial_port().baud_rate().unwrap(), 8_000_000);
+    for motor_id in [1, 2] {
+        assert_eq!(bus.read_baud_rate(motor_id).unwrap().data, Baud::B8000000);
+    }
+}
+
+#[test]
+fn change_baud_rate_restores_the_old_rate_on_failure() {
+    let mut bus = Bus::new(FaultInjector::new(SimBus::with_motors(&[1]))).unwrap();
+    bus.enable_torque(1).unwrap();
+    assert!(matches!(
+        bus.change_baud_rate(1, Baud::B2000000),
+        Err(ChangeBaudRateError::Rejected { motor_id: 1 })
+    ));
+    assert_eq!(bus.serial_port().baud_rate().unwrap(), 8_000_000);
+
+    // The write is accepted, but the acknowledgement of the save is corrupted.
+    bus.disable_torque(1).unwrap();
+    bus.serial_port().inject(Fault::Pass);
+    bus.serial_port().inject(Fault::Pass);
+    bus.serial_port().inject(Fault::CorruptChecksum);
+    assert!(matches!(
+        bus.change_baud_rate(1, Baud::B2000000),
+        Err(ChangeBaudRateError::Save(_))
+    ));
+    assert_eq!(bus.serial_port().baud_rate().unwrap(), 8_000_000);
+}
+
+#[test]
+fn baud_rates_are_validated() {
+    assert_eq!(Baud::from_bits_per_second(460_800), Some(Baud::B460800));
+    assert_eq!(Baud::from_bits_per_second(9600), None);
+    assert_eq!(u32::from(Baud::B115200), 6);
+    assert!(Baud::try_from(7).is_err());
+}
@@ -0,0 +1,111 @@
+//! Tests for the register metadata table and the untyped register access built on it.
+
+use strum::IntoEnumIterator;
+use ww_bear::error::{InvalidValueError, TransferError, WriteError};
+use ww_bear::registers::{REGISTERS, Register, config, status};
+use ww_bear::sim::SimBus;
+use ww_bear::{
+    Access, AnyRegister, Bus, ConfigRegister, OperatingMode, RegisterBank, RegisterValue, StatusRegister, ValueType,
+};
+
+#[test]
+fn table_lists_every_register() {
+    assert_eq!(
+        REGISTERS.len(),
+        ConfigRegister::iter().count() + StatusRegister::iter().count()
+    );
+    for register in ConfigRegister::iter() {
+        let info = register.info();
+        assert_eq!(info.bank, RegisterBank::Config);
+        assert_eq!(info.address, register as u8);
+        assert_eq!(info.name, register.to_string());
+        assert!(REGISTERS.contains(&info));
+    }
+    for register in StatusRegister::iter() {
+        let info = register.info();
+        assert_eq!(info.bank, RegisterBank::Status);
+        assert_eq!(info.address, register as u8);
+        assert!(REGISTERS.contains(&info));
+    }
+}
+
+#[test]
+fn info_describes_the_register() {
+    let info = StatusRegister::PresentPos.info();
+    assert_eq!(info, status::PresentPos::INFO);
+    assert_eq!(info.name, "PresentPos");
+    assert_eq!(info.value_type, ValueType::F32);
+    assert_eq!(info.access, Access::ReadOnly);
+    assert_eq!(info.unit, Some("rad"));
+    assert!(!info.description.is_empty());
+
+    let info = config::Mode::INFO;
+    assert_eq!(info.value_type, ValueType::U32);
+    assert_eq!(info.access, Access::ReadWrite);
+    assert_eq!(info.unit, None);
+    assert_eq!(ConfigRegister::TempLimitHigh.info().unit, Some("°C"));
+}
+
+#[test]
+fn values_round_trip() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1])).unwrap();
+    bus.write_value(1, StatusRegister::GoalPos.into(), RegisterValue::F32(1.5))
+        .unwrap();
+    assert_eq!(bus.read_goal_pos(1).unwrap().data, 1.5);
+    assert_eq!(
+        bus.read_value(1, StatusRegister::GoalPos.into()).unwrap().data,
+        RegisterValue::F32(1.5)
+    );
+
+    bus.write_value(
+        1,
+        ConfigRegister::Mode.into(),
+        RegisterValue::U32(OperatingMode::Velocity.into()),
+    )
+    .unwrap();
+    assert_eq!(bus.read_mode(1).unwrap().data, OperatingMode::Velocity);
+    let mode = bus
+        .read_value(1, AnyRegister::Config(ConfigRegister::Mode))
+        .unwrap()
+        .data;
+    assert_eq!(mode.as_u32(), Some(OperatingMode::Velocity.into()));
+}
+
+#[test]
+fn invalid_values_are_rejected() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1])).unwrap();
+    match bus.write_value(1, ConfigRegister::Mode.into(), RegisterValue::F32(2.0)) {
+        Err(TransferError::WriteError(WriteError::InvalidValue(InvalidValueError::TypeMismatch {
+            register,
+            expected,
+            actual,
+        }))) => {
+            assert_eq!(register, "Mode");
+            assert_eq!(expected, ValueType::U32);
+            assert_eq!(actual, ValueType::F32);
+        },
+        other => panic!("expected a type mismatch, got {other:?}"),
+    }
+    assert!(matches!(
+        bus.write_value(1, StatusRegister::PresentPos.into(), RegisterValue::F32(0.0)),
+        Err(TransferError::WriteError(WriteError::InvalidValue(
+            InvalidValueError::ReadOnly { .. }
+        )))
+    ));
+    assert_eq!(
+        bus.write_value(1, ConfigRegister::Mode.into(), RegisterValue::U32(9))
+            .unwrap_err()
+            .to_string(),
+        "register Mode can not hold the value 9"
+    );
+    assert!(matches!(
+        bus.write_value(1, StatusRegister::TorqueEnable.into(), RegisterValue::U32(7)),
+        Err(TransferError::WriteError(WriteError::InvalidValue(
+            InvalidValueError::UnknownValue {
+                register: "TorqueEnable",
+                value: 7,
+            }
+        )))
+    ));
+    assert_eq!(bus.read_mode(1).unwrap().data, OperatingMode::Torque);
+}
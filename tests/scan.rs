@@ -0,0 +1,83 @@
+//! Tests for bus scanning, using the simulated bus from `ww_bear::sim`.
+
+use std::time::Duration;
+
+use ww_bear::sim::{Fault, FaultInjector, SimBus, SimMotor};
+use ww_bear::{Baud, Bus, ConfigRegister, ErrorFlags, FoundMotor};
+
+const TIMEOUT: Duration = Duration::from_millis(1);
+
+#[test]
+fn scan_reports_present_motors_with_their_flags() {
+    let mut sim = SimBus::with_motors(&[1, 4, 7]);
+    sim.motor_mut(4).unwrap().set_error_flags(ErrorFlags::HARDWARE);
+    let mut bus = Bus::new(sim).unwrap();
+
+    let found = bus.scan_alloc(0..=10, TIMEOUT).unwrap();
+    let ids: Vec<_> = found.iter().map(|motor| motor.motor_id).collect();
+    assert_eq!(ids, [1, 4, 7]);
+    assert_eq!(found[1].warning, ErrorFlags::HARDWARE);
+    assert!(found.iter().all(|motor| motor.baud_rate == 8_000_000));
+}
+
+#[test]
+fn scan_waits_briefly_for_missing_motors() {
+    let mut bus = Bus::new(SimBus::new()).unwrap();
+    let start = bus.serial_port().now();
+    assert!(bus.scan_alloc(0..=0xFD, TIMEOUT).unwrap().is_empty());
+    let elapsed = bus.serial_port().now() - start;
+    assert!(elapsed < Duration::from_millis(300), "scan took {elapsed:?}");
+}
+
+#[test]
+fn scan_skips_garbled_replies() {
+    let sim = FaultInjector::new(SimBus::with_motors(&[1, 2, 3]));
+    let mut bus = Bus::with_buffers(sim, vec![0; 32], vec![0; 32]).unwrap();
+    // A bad checksum, then a header whose length does not fit the read buffer.
+    bus.serial_port().inject(Fault::CorruptChecksum);
+    let header = vec![0xFF, 0xFF, 0x02, 0x40];
+    bus.serial_port().inject(Fault::LeadingGarbage(header));
+
+    let found = bus.scan_alloc(1..=3, TIMEOUT).unwrap();
+    let ids: Vec<_> = found.iter().map(|motor| motor.motor_id).collect();
+    assert_eq!(ids, [3]);
+}
+
+#[test]
+fn baud_rate_scan_finds_motors_at_any_supported_rate() {
+    let mut sim = SimBus::with_motors(&[1]);
+    let mut slow = SimMotor::new(2);
+    slow.set_config_u32(ConfigRegister::BaudRate, Baud::B115200.into());
+    sim.add_motor(slow);
+    let mut bus = Bus::new(sim).unwrap();
+
+    assert_eq!(bus.scan_alloc(1..=2, TIMEOUT).unwrap().len(), 1);
+    let found = bus.scan_baud_rates_alloc(1..=2, TIMEOUT).unwrap();
+    assert_eq!(
+        found,
+        [
+            FoundMotor {
+                motor_id: 1,
+                warning: ErrorFlags::empty(),
+                baud_rate: 8_000_000,
+            },
+            FoundMotor {
+                motor_id: 2,
+                warning: ErrorFlags::empty(),
+                baud_rate: 115_200,
+            },
+        ]
+    );
+    // The original baud rate is restored.
+    assert!(bus.ping(1).is_ok());
+}
+
+#[tokio::test]
+async fn async_scan() {
+    let mut bus = ww_bear::asynchronous::Bus::new(SimBus::with_motors(&[3])).unwrap();
+    let mut found = Vec::new();
+    bus.scan(1..5, TIMEOUT, |motor| found.push(motor.motor_id))
+        .await
+        .unwrap();
+    assert_eq!(found, [3]);
+}
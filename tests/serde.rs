@@ -0,0 +1,45 @@
+//! Tests for the `serde` implementations.
+
+use ww_bear::sim::SimBus;
+use ww_bear::{BulkWriteData, Bus, ConfigRegister, ErrorFlags, MotorConfig, OperatingMode, Response};
+
+#[test]
+fn error_flags_are_a_list_of_names() {
+    let flags = ErrorFlags::OVERHEAT | ErrorFlags::JOINT_LIMIT;
+    let json = serde_json::to_string(&flags).unwrap();
+    assert_eq!(json, r#"["OVERHEAT","JOINT_LIMIT"]"#);
+    assert_eq!(serde_json::from_str::<ErrorFlags>(&json).unwrap(), flags);
+    assert_eq!(serde_json::from_str::<ErrorFlags>("[]").unwrap(), ErrorFlags::empty());
+    assert!(serde_json::from_str::<ErrorFlags>(r#"["ON_FIRE"]"#).is_err());
+}
+
+#[test]
+fn responses_and_bulk_data_round_trip() {
+    let response = Response {
+        motor_id: 3,
+        warning: ErrorFlags::COMMUNICATION,
+        data: 1.5f32,
+    };
+    let json = serde_json::to_string(&response).unwrap();
+    assert_eq!(json, r#"{"motor_id":3,"warning":["COMMUNICATION"],"data":1.5}"#);
+    assert_eq!(serde_json::from_str::<Response<f32>>(&json).unwrap(), response);
+
+    let data = BulkWriteData::from_u32(2, 7);
+    let json = serde_json::to_string(&data).unwrap();
+    assert_eq!(serde_json::from_str::<BulkWriteData<[u8; 4]>>(&json).unwrap(), data);
+    assert_eq!(
+        serde_json::to_string(&ConfigRegister::LimitIMax).unwrap(),
+        r#""LimitIMax""#
+    );
+}
+
+#[test]
+fn motor_config_round_trips_through_toml() {
+    let mut bus = Bus::new(SimBus::with_motors(&[1])).unwrap();
+    bus.write_mode(1, OperatingMode::Position).unwrap();
+    let config = bus.read_all_config(1).unwrap();
+    let toml = toml::to_string(&config).unwrap();
+    assert!(toml.contains("mode = \"Position\""));
+    assert!(toml.contains("p_gain_pos = "));
+    assert_eq!(toml::from_str::<MotorConfig>(&toml).unwrap(), config);
+}
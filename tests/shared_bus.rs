@@ -0,0 +1,97 @@
+//! Tests for sharing an asynchronous bus between tasks, using the simulated bus from `ww_bear::sim`.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::Duration;
+
+use ww_bear::asynchronous::{Bus, Priority, SharedBus};
+use ww_bear::sim::SimBus;
+
+fn shared(motor_ids: &[u8]) -> SharedBus<SimBus> {
+    SharedBus::new(Bus::new(SimBus::with_motors(motor_ids)).unwrap())
+}
+
+#[tokio::test]
+async fn tasks_share_the_bus() {
+    let bus = shared(&[1, 2]);
+    let tasks = [1, 2].map(|motor_id| {
+        let bus = bus.clone();
+        tokio::spawn(async move {
+            for i in 0..10 {
+                let goal = f32::from(motor_id) + i as f32;
+                let mut bus = bus.lock().await;
+                bus.write_goal_vel(motor_id, goal).await.unwrap();
+                assert_eq!(bus.read_goal_vel(motor_id).await.unwrap().data, goal);
+            }
+        })
+    });
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let mut bus = bus.try_into_inner().unwrap();
+    assert_eq!(bus.read_goal_vel(1).await.unwrap().data, 10.0);
+    assert_eq!(bus.read_goal_vel(2).await.unwrap().data, 11.0);
+}
+
+#[tokio::test]
+async fn real_time_tasks_go_first() {
+    let bus = shared(&[1]);
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let guard = bus.lock().await;
+
+    let wait = |priority: Priority, name: &'static str| {
+        let bus = bus.clone();
+        let order = order.clone();
+        async move {
+            let mut bus = bus.lock_with_priority(priority).await;
+            bus.ping(1).await.unwrap();
+            order.lock().unwrap().push(name);
+        }
+    };
+    let release = async move {
+        tokio::task::yield_now().await;
+        drop(guard);
+    };
+    tokio::join!(
+        wait(Priority::Normal, "first normal"),
+        wait(Priority::Normal, "second normal"),
+        wait(Priority::RealTime, "real-time"),
+        release,
+    );
+    assert_eq!(*order.lock().unwrap(), ["real-time", "first normal", "second normal"]);
+}
+
+#[tokio::test]
+async fn abandoned_waiters_pass_the_bus_on() {
+    let bus = shared(&[1]);
+    let guard = bus.lock().await;
+    let mut abandoned = Box::pin(bus.lock_realtime());
+    assert!(poll_once(abandoned.as_mut()).await.is_none());
+    let waiting = tokio::spawn({
+        let bus = bus.clone();
+        async move {
+            bus.lock().await.ping(1).await.unwrap();
+        }
+    });
+    tokio::task::yield_now().await;
+
+    // Releasing the bus wakes the real-time waiter, which gives up and has to wake the next one.
+    drop(guard);
+    drop(abandoned);
+    tokio::time::timeout(Duration::from_secs(1), waiting)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+/// Poll a future once, returning its output if it is ready.
+async fn poll_once<F: Future + Unpin>(mut future: F) -> Option<F::Output> {
+    std::future::poll_fn(|cx| match Pin::new(&mut future).poll(cx) {
+        Poll::Ready(output) => Poll::Ready(Some(output)),
+        Poll::Pending => Poll::Ready(None),
+    })
+    .await
+}
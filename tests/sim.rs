@@ -0,0 +1,380 @@
+//! Tests for the simulated bus in `ww_bear::sim`, driven through the real `Bus`.
+
+use std::time::Duration;
+
+use ww_bear::error::{InvalidMessage, ReadError, TransferError};
+use ww_bear::sim::{SimBus, SimError};
+use ww_bear::{
+    BulkWriteData, Bus, ConfigRegister, ErrorFlags, ErrorPolicy, OperatingMode, SerialPort, StatusRegister, TorqueState,
+};
+
+fn open(motor_ids: &[u8]) -> Bus<SimBus, Vec<u8>> {
+    Bus::new(SimBus::with_motors(motor_ids)).unwrap()
+}
+
+#[test]
+fn ping_answers_only_present_motors() {
+    let mut bus = open(&[1, 2]);
+    assert_eq!(bus.ping(1).unwrap().motor_id, 1);
+    assert_eq!(bus.ping(2).unwrap().motor_id, 2);
+    match bus.ping(3) {
+        Err(TransferError::ReadError(ReadError::Io(SimError::Timeout))) => (),
+        other => panic!("expected a timeout, got {other:?}"),
+    }
+}
+
+#[test]
+fn packets_with_a_short_length_are_dropped() {
+    let mut bus = open(&[1]);
+    for len in [0, 1] {
+        bus.serial_port()
+            .write_all(&[0xFF, 0xFF, 0x01, len, 0xFE - len])
+            .unwrap();
+    }
+    assert_eq!(bus.ping(1).unwrap().motor_id, 1);
+}
+
+#[test]
+fn registers_round_trip() {
+    let mut bus = open(&[1]);
+    bus.write_goal_pos(1, 1.25).unwrap();
+    bus.write_p_gain_pos(1, 3.5).unwrap();
+    assert_eq!(bus.read_goal_pos(1).unwrap().data, 1.25);
+    assert_eq!(bus.read_p_gain_pos(1).unwrap().data, 3.5);
+
+    let motor = bus.serial_port().motor(1).unwrap();
+    assert_eq!(motor.status_f32(StatusRegister::GoalPos), 1.25);
+    assert_eq!(motor.config_f32(ConfigRegister::PGainPos), 3.5);
+}
+
+#[test]
+fn writes_are_acknowledged() {
+    let mut bus = open(&[1]);
+    let ack = bus.write_goal_pos(1, 0.5).unwrap();
+    assert_eq!(ack.motor_id, 1);
+    assert!(ack.warning.is_empty());
+    assert_eq!(bus.save_config(1).unwrap().motor_id, 1);
+    match bus.write_goal_pos(2, 0.5) {
+        Err(TransferError::ReadError(ReadError::Io(SimError::Timeout))) => (),
+        other => panic!("expected a timeout, got {other:?}"),
+    }
+}
+
+#[test]
+fn mode_is_typed() {
+    let mut bus = open(&[1]);
+    bus.write_mode(1, OperatingMode::DirectForce).unwrap();
+    assert_eq!(bus.read_mode(1).unwrap().data, OperatingMode::DirectForce);
+
+    bus.serial_port()
+        .motor_mut(1)
+        .unwrap()
+        .set_config_u32(ConfigRegister::Mode, 7);
+    match bus.read_mode(1) {
+        Err(TransferError::ReadError(ReadError::InvalidMessage(InvalidMessage::InvalidRegisterValue(e)))) => {
+            assert_eq!(e.value, 7);
+        },
+        other => panic!("expected an invalid register value, got {other:?}"),
+    }
+}
+
+#[test]
+fn broadcast_and_unacknowledged_writes_do_not_wait() {
+    let mut bus = open(&[1, 2]);
+    assert_eq!(bus.write_goal_pos(0xFE, 0.25).unwrap().motor_id, 0xFE);
+    bus.save_config(0xFE).unwrap();
+    assert_eq!(bus.read_goal_pos(1).unwrap().data, 0.25);
+    assert_eq!(bus.read_goal_pos(2).unwrap().data, 0.25);
+
+    bus.set_acknowledge_writes(false);
+    bus.write_goal_pos(3, 0.5).unwrap();
+    bus.write_goal_pos(1, 0.5).unwrap();
+    assert_eq!(bus.read_goal_pos(1).unwrap().data, 0.5);
+}
+
+#[test]
+fn config_writes_are_ignored_while_torque_is_enabled() {
+    let mut bus = open(&[1]);
+    let gain = bus.read_p_gain_pos(1).unwrap().data;
+    bus.enable_torque(1).unwrap();
+    bus.write_p_gain_pos(1, gain + 1.0).unwrap();
+    assert_eq!(bus.read_p_gain_pos(1).unwrap().data, gain);
+}
+
+#[test]
+fn save_config_persists_the_config_table() {
+    let mut bus = open(&[1]);
+    bus.write_limit_acc_max(1, 12.0).unwrap();
+    assert_eq!(
+        bus.serial_port()
+            .motor(1)
+            .unwrap()
+            .saved_config_f32(ConfigRegister::LimitAccMax),
+        0.0
+    );
+    bus.save_config(1).unwrap();
+    assert_eq!(
+        bus.serial_port()
+            .motor(1)
+            .unwrap()
+            .saved_config_f32(ConfigRegister::LimitAccMax),
+        12.0
+    );
+}
+
+#[test]
+fn set_absolute_position_rehomes_the_motor() {
+    let mut bus = open(&[1]);
+    bus.serial_port()
+        .motor_mut(1)
+        .unwrap()
+        .set_status_f32(StatusRegister::PresentPos, 2.0);
+    bus.set_absolute_position(1, 0.5, 0.0).unwrap();
+    assert_eq!(bus.read_present_pos(1).unwrap().data, 0.5);
+    assert_eq!(bus.read_homing_offset(1).unwrap().data, 1.5);
+}
+
+#[test]
+fn set_absolute_position_handles_extreme_positions() {
+    let mut bus = open(&[1]);
+    assert!(bus.set_absolute_position(1, f32::INFINITY, 0.1).is_err());
+    assert!(bus.set_absolute_position(1, f32::NAN, 0.0).is_err());
+    // Too large for a turn to change the value, which must still wrap instead of looping forever.
+    let _ = bus.set_absolute_position(1, 1e10, 0.1);
+    let motor = bus.serial_port().motor(1).unwrap();
+    assert_eq!(motor.saved_config_f32(ConfigRegister::HomingOffset), 0.0);
+}
+
+#[test]
+fn bulk_read_write_reaches_every_motor() {
+    let mut bus = open(&[1, 2, 3]);
+    for (id, pos) in [(1, 0.1f32), (2, 0.2), (3, 0.3)] {
+        bus.serial_port()
+            .motor_mut(id)
+            .unwrap()
+            .set_status_f32(StatusRegister::PresentPos, pos);
+    }
+
+    let devices = [1, 2, 3].map(|id| BulkWriteData::from_f32(id, f32::from(id)));
+    let replies = bus
+        .bulk_read_write_alloc(devices, &[StatusRegister::PresentPos], &[StatusRegister::GoalPos])
+        .unwrap();
+
+    let positions: Vec<_> = replies.iter().map(|r| r.as_ref().unwrap().f32(0).unwrap()).collect();
+    assert_eq!(positions, [0.1, 0.2, 0.3]);
+    for id in [1, 2, 3] {
+        assert_eq!(bus.read_goal_pos(id).unwrap().data, f32::from(id));
+    }
+}
+
+#[test]
+fn bulk_read_reports_missing_motors() {
+    let mut bus = open(&[1, 3]);
+    let replies = bus.bulk_read_alloc(&[1, 2, 3], &[StatusRegister::PresentPos]).unwrap();
+    assert_eq!(replies.len(), 3);
+    assert_eq!(replies[0].as_ref().unwrap().motor_id, 1);
+    // Motor 2 is missing, so its slot receives motor 3's reply and the last slot times out.
+    assert!(replies[1].is_err());
+    assert!(matches!(replies[2], Err(ReadError::Io(SimError::Timeout))));
+}
+
+#[test]
+fn position_mode_tracks_the_goal() {
+    let mut bus = open(&[1]);
+    bus.write_mode(1, OperatingMode::Position).unwrap();
+    bus.enable_torque(1).unwrap();
+    bus.write_goal_pos(1, 1.0).unwrap();
+
+    bus.serial_port().advance(Duration::from_millis(100));
+    let moving = bus.read_present_pos(1).unwrap().data;
+    assert!(
+        moving > 0.1 && moving < 1.0,
+        "position {moving} should be on its way to the goal"
+    );
+
+    bus.serial_port().advance(Duration::from_secs(2));
+    let settled = bus.read_present_pos(1).unwrap().data;
+    assert!(
+        (settled - 1.0).abs() < 1e-3,
+        "position {settled} should settle at the goal"
+    );
+    assert!(bus.read_present_vel(1).unwrap().data.abs() < 1e-2);
+}
+
+#[test]
+fn velocity_mode_tracks_the_goal() {
+    let mut bus = open(&[1]);
+    bus.write_mode(1, OperatingMode::Velocity).unwrap();
+    bus.enable_torque(1).unwrap();
+    bus.write_goal_vel(1, 5.0).unwrap();
+
+    bus.serial_port().advance(Duration::from_secs(2));
+    let velocity = bus.read_present_vel(1).unwrap().data;
+    assert!(
+        (velocity - 5.0).abs() < 1e-2,
+        "velocity {velocity} should settle at the goal"
+    );
+    assert!(bus.read_present_pos(1).unwrap().data > 5.0);
+}
+
+#[test]
+fn torque_mode_heats_the_winding() {
+    let mut bus = open(&[1]);
+    bus.enable_torque(1).unwrap();
+    bus.write_goal_iq(1, 5.0).unwrap();
+
+    bus.serial_port().advance(Duration::from_millis(10));
+    assert!((bus.read_present_iq(1).unwrap().data - 5.0).abs() < 1e-2);
+    assert!(bus.read_present_vel(1).unwrap().data > 0.0);
+
+    let before = bus.read_winding_temp(1).unwrap().data;
+    bus.serial_port().advance(Duration::from_secs(10));
+    let after = bus.read_winding_temp(1).unwrap().data;
+    assert!(
+        after > before + 1.0,
+        "winding temperature should rise from {before}, got {after}"
+    );
+}
+
+#[test]
+fn disabled_motor_does_not_move() {
+    let mut bus = open(&[1]);
+    bus.write_mode(1, OperatingMode::Position).unwrap();
+    bus.write_goal_pos(1, 1.0).unwrap();
+    bus.serial_port().advance(Duration::from_secs(1));
+    assert_eq!(bus.read_present_pos(1).unwrap().data, 0.0);
+    assert_eq!(bus.read_present_iq(1).unwrap().data, 0.0);
+}
+
+#[tokio::test]
+async fn async_bus_talks_to_the_simulator() {
+    let mut bus = ww_bear::asynchronous::Bus::new(SimBus::with_motors(&[4])).unwrap();
+    bus.ping(4).await.unwrap();
+    bus.write_goal_vel(4, -2.0).await.unwrap();
+    assert_eq!(bus.read_goal_vel(4).await.unwrap().data, -2.0);
+}
+
+#[test]
+fn torque_helpers_set_the_torque_state() {
+    let mut bus = open(&[1, 2]);
+    bus.enable_torque(1).unwrap();
+    assert_eq!(bus.read_torque_enable(1).unwrap().data, TorqueState::Enabled);
+    bus.disable_torque(1).unwrap();
+    assert_eq!(bus.read_torque_enable(1).unwrap().data, TorqueState::Disabled);
+
+    bus.bulk_enable_torque(&[1, 2]).unwrap();
+    assert!(bus.serial_port().motors().all(|motor| motor.torque_enabled()));
+    bus.bulk_disable_torque(&[1, 2]).unwrap();
+    assert!(bus.serial_port().motors().all(|motor| !motor.torque_enabled()));
+}
+
+#[test]
+fn estop_disables_torque_and_raises_the_flag() {
+    let mut bus = open(&[1, 2]);
+    bus.bulk_enable_torque(&[1, 2]).unwrap();
+    bus.bulk_estop(&[1, 2]).unwrap();
+    for motor in bus.serial_port().motors() {
+        assert!(!motor.torque_enabled());
+        assert_eq!(motor.error_flags(), ErrorFlags::WATCHDOG_ESTOP);
+    }
+
+    bus.set_error_policy(ErrorPolicy::Ignore);
+    let reply = bus.read_torque_enable(1).unwrap();
+    assert_eq!(reply.data, TorqueState::Estop);
+    assert_eq!(reply.warning, ErrorFlags::WATCHDOG_ESTOP);
+    assert!(bus.disable_torque(1).unwrap().warning.is_empty());
+
+    bus.set_error_policy(ErrorPolicy::Strict);
+    bus.enable_torque(1).unwrap();
+    assert_eq!(bus.estop(1).unwrap().warning, ErrorFlags::WATCHDOG_ESTOP);
+    assert!(!bus.serial_port().motor(1).unwrap().torque_enabled());
+}
+
+#[test]
+fn split_bulk_transfers_merge_per_motor() {
+    let ids: Vec<u8> = (1..=12).collect();
+    let mut bus = open(&ids);
+    let writes = [
+        StatusRegister::GoalId,
+        StatusRegister::GoalIq,
+        StatusRegister::GoalVel,
+        StatusRegister::GoalPos,
+    ];
+    let devices: Vec<_> = ids
+        .iter()
+        .map(|&id| {
+            let data: Vec<u8> = (0..4)
+                .flat_map(|i| (f32::from(id) + i as f32 / 10.0).to_le_bytes())
+                .collect();
+            BulkWriteData { motor_id: id, data }
+        })
+        .collect();
+    // 12 motors writing 4 registers each do not fit a single 128-byte packet.
+    assert!(bus.bulk_write(&devices, &writes).is_err());
+    bus.bulk_write_split(&devices, &writes).unwrap();
+
+    // 16 read registers do not fit the 4-bit count of a single packet.
+    let mut reads = vec![StatusRegister::GoalPos; 12];
+    reads.extend(writes);
+    let replies = bus.bulk_read_write_split(&devices, &reads, &writes).unwrap();
+    assert_eq!(replies.len(), 12);
+    for (reply, &id) in replies.iter().zip(&ids) {
+        let reply = reply.as_ref().unwrap();
+        assert_eq!(reply.motor_id, id);
+        assert_eq!(reply.data.len(), 16 * 4);
+        assert_eq!(reply.f32(0), Some(f32::from(id) + 0.3));
+        assert_eq!(reply.f32(12), Some(f32::from(id)));
+        assert_eq!(reply.f32(15), Some(f32::from(id) + 0.3));
+    }
+}
+
+#[test]
+fn split_bulk_reads_report_failed_motors() {
+    let mut bus = open(&[1, 3]);
+    let reads = [StatusRegister::PresentPos; 20];
+    let replies = bus.bulk_read_split(&[1, 3, 2], &reads).unwrap();
+    assert_eq!(replies[0].as_ref().unwrap().data.len(), 80);
+    assert_eq!(replies[1].as_ref().unwrap().motor_id, 3);
+    assert!(matches!(replies[2], Err(ReadError::Io(SimError::Timeout))));
+}
+
+#[test]
+fn typed_bulk_reads_decode_each_register() {
+    use ww_bear::registers::status::{PresentPos, PresentVel, TorqueEnable};
+
+    let mut bus = open(&[1, 2]);
+    bus.enable_torque(2).unwrap();
+    for id in [1, 2] {
+        bus.serial_port()
+            .motor_mut(id)
+            .unwrap()
+            .set_status_f32(StatusRegister::PresentPos, f32::from(id) / 2.0);
+    }
+    let mut replies = Vec::new();
+    bus.bulk_read_typed::<(PresentPos, TorqueEnable)>(&[1, 2], |reply| replies.push(reply.unwrap()))
+        .unwrap();
+    assert_eq!(replies[0].data, (0.5, TorqueState::Disabled));
+    assert_eq!(replies[1].motor_id, 2);
+    assert_eq!(replies[1].data, (1.0, TorqueState::Enabled));
+
+    let replies = bus.bulk_read_typed_alloc::<(PresentVel,)>(&[1, 3]).unwrap();
+    assert_eq!(replies[0].as_ref().unwrap().data, (0.0,));
+    assert!(matches!(replies[1], Err(ReadError::Io(SimError::Timeout))));
+}
+
+#[test]
+fn typed_bulk_writes_encode_each_register() {
+    use ww_bear::registers::status::{GoalPos, GoalVel, TorqueEnable};
+
+    let mut bus = open(&[1, 2]);
+    bus.bulk_write_typed::<(GoalPos, GoalVel)>([(1, (0.5, -1.0)), (2, (1.5, 2.0))])
+        .unwrap();
+    assert_eq!(bus.read_goal_pos(1).unwrap().data, 0.5);
+    assert_eq!(bus.read_goal_vel(1).unwrap().data, -1.0);
+    assert_eq!(bus.read_goal_pos(2).unwrap().data, 1.5);
+    assert_eq!(bus.read_goal_vel(2).unwrap().data, 2.0);
+
+    bus.bulk_write_typed::<(TorqueEnable,)>([1, 2].map(|id| (id, (TorqueState::Enabled,))))
+        .unwrap();
+    assert!(bus.serial_port().motor(2).unwrap().torque_enabled());
+}
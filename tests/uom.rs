@@ -0,0 +1,46 @@
+//! Tests for the `uom` register helpers, using the simulated bus from `ww_bear::sim`.
+
+use uom::si::angle::{degree, radian};
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::f32::{Angle, AngularVelocity};
+use uom::si::thermodynamic_temperature::{degree_celsius, kelvin};
+use ww_bear::sim::SimBus;
+use ww_bear::{Bus, ConfigRegister, StatusRegister, registers::status};
+
+fn open() -> Bus<SimBus, Vec<u8>> {
+    Bus::new(SimBus::with_motors(&[1])).unwrap()
+}
+
+#[test]
+fn quantities_are_converted_to_the_motor_units() {
+    let mut bus = open();
+    bus.write_goal_pos_quantity(1, Angle::new::<degree>(90.0)).unwrap();
+    bus.write_goal_vel_quantity(1, AngularVelocity::new::<radian_per_second>(2.5))
+        .unwrap();
+    let motor = bus.serial_port().motor(1).unwrap();
+    assert!((motor.status_f32(StatusRegister::GoalPos) - core::f32::consts::FRAC_PI_2).abs() < 1e-6);
+    assert_eq!(motor.status_f32(StatusRegister::GoalVel), 2.5);
+
+    bus.serial_port()
+        .motor_mut(1)
+        .unwrap()
+        .set_status_f32(StatusRegister::PresentPos, 1.5);
+    assert_eq!(bus.read_present_pos_quantity(1).unwrap().data.get::<radian>(), 1.5);
+    let position = bus.read_quantity::<status::PresentPos>(1).unwrap().data;
+    assert_eq!(position.get::<radian>(), 1.5);
+}
+
+#[test]
+fn temperatures_are_in_celsius() {
+    let mut bus = open();
+    let limit = bus.read_temp_limit_high_quantity(1).unwrap().data;
+    let celsius = bus
+        .serial_port()
+        .motor(1)
+        .unwrap()
+        .config_f32(ConfigRegister::TempLimitHigh);
+    assert!((limit.get::<degree_celsius>() - celsius).abs() < 1e-3);
+    assert!((limit.get::<kelvin>() - (celsius + 273.15)).abs() < 1e-3);
+    let winding = bus.read_winding_temp_quantity(1).unwrap().data;
+    assert!((winding.get::<degree_celsius>() - 25.0).abs() < 1e-3);
+}